@@ -1,101 +1,580 @@
-use std::str::FromStr;
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    num::IntErrorKind,
+    ops::{Range, RangeInclusive},
+    str::FromStr,
+};
 
 use itertools::Itertools;
 
-use crate::calculator::{CORE_LENGTH, Letter, Operation};
+use crate::calculator::{
+    CORE_LENGTH, CORE_LENGTHS, DeadBranch, Letter,
+    encoding::LetterEncoding,
+    fraction::Fraction,
+    operator::{Operation, OperationPool},
+    pattern::{ParsePatternError, Pattern},
+    rules::{Arithmetic, CoreRules},
+    search::{NumberSearch, SearchLimits, SearchStop, search},
+    table::{CoreTable, MAX_CORE_LENGTH},
+};
 
 /**
-    Computes the numeric core from the input 4-letter `word`
+    Computes the numeric core from the input `word`
 
-    Input `word` must be a 4 alphabetic character string.
-    Both uppercase and lowercase are allowed and produce the same result
+    Input `word` must be an alphabetic character string, 4 characters long for the game's cores.
+    Any length in [`CORE_LENGTHS`] is accepted.
+    Both uppercase and lowercase are allowed and produce the same result.
+    Letters are converted to numbers with the [`LetterEncoding`] of the `rules`
+
+    Looked up in the [`CoreTable`] of these rules if it was already built
 
     # Errors
     - Invalid input
     - No solution found
 */
-pub fn decrypt_word(word: &str) -> Result<u32, DecryptError> {
-    if word.len() != CORE_LENGTH {
-        return Err(DecryptError::InputWordLen);
+pub fn decrypt_word(word: &str, rules: &CoreRules) -> Result<u32, DecryptError> {
+    let positions = word_to_positions(word)?;
+    let table = CoreTable::cached(positions.len(), rules);
+    decrypt_letters(&positions, table.as_deref(), rules)
+}
+
+/// Core of a word given as positions in the alphabet, looked up in the `table` if there is one
+fn decrypt_letters(
+    positions: &[u32],
+    table: Option<&CoreTable>,
+    rules: &CoreRules,
+) -> Result<u32, DecryptError> {
+    match table {
+        Some(table) => table.core(positions),
+        None => {
+            let numbers = positions
+                .iter()
+                .map(|&position| rules.encoding.number_at(position))
+                .collect::<Vec<_>>();
+            decrypt_numbers(&numbers, rules)
+        }
+    }
+}
+
+/// Whether the `word` has unknown letters, written `?` or `[AEIOU]`
+pub fn has_wildcards(word: &str) -> bool {
+    word.contains(['?', '['])
+}
+
+/**
+    Computes every core a `word` with unknown letters can have, like `PE?K`
+
+    Unknown letters are written `?` for any letter, or `[AEIOU]` for a set of letters.
+    Returns the cores in increasing order, each with the letters replacing the wildcards to produce it.
+    Letters are tried in alphabetical order, until every word was tried or the `limits` are reached
+
+    # Errors
+    - Invalid input
+    - No solution found, whatever the unknown letters are
+*/
+pub fn decrypt_wildcard_word(
+    word: &str,
+    rules: &CoreRules,
+    limits: &SearchLimits,
+) -> Result<WildcardSearch, DecryptError> {
+    let pattern = word.parse::<Pattern>().map_err(|error| match error {
+        ParsePatternError::Length => DecryptError::InputWordLen,
+        error => DecryptError::InputPattern(error),
+    })?;
+    let wildcards = pattern
+        .slots()
+        .iter()
+        .positions(|slot| slot.len() > 1)
+        .collect::<Vec<_>>();
+    let table = CoreTable::cached(pattern.len(), rules);
+
+    let (found, stopped) = search(pattern.combinations(), limits, |index| {
+        let mut positions = [0; MAX_CORE_LENGTH];
+        let positions = &mut positions[..pattern.len()];
+        pattern.write_numbers(index, positions);
+        let core = decrypt_letters(positions, table.as_deref(), rules).ok()?;
+        let fill = wildcards
+            .iter()
+            .filter_map(|&slot| Letter::try_from_num(positions[slot]))
+            .collect::<Vec<_>>();
+        Some((core, fill))
+    });
+
+    if found.is_empty() && stopped.is_none() {
+        return Err(DecryptError::NoSolution);
+    }
+    let mut cores = BTreeMap::<u32, Vec<Vec<Letter>>>::new();
+    for (core, fill) in found {
+        cores.entry(core).or_default().push(fill);
+    }
+    let cores = cores
+        .into_iter()
+        .map(|(core, fills)| WildcardCore { core, fills })
+        .collect();
+    Ok(WildcardSearch { cores, stopped })
+}
+
+/// Cores found by [`decrypt_wildcard_word`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WildcardSearch {
+    pub cores: Vec<WildcardCore>,
+    /// `None` if every word was tried
+    pub stopped: Option<SearchStop>,
+}
+
+/// A core reachable by a word with unknown letters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WildcardCore {
+    pub core: u32,
+    /// Letters replacing the wildcards of the word, in alphabetical order
+    pub fills: Vec<Vec<Letter>>,
+}
+
+/**
+    Decrypts the `words`, then regroups the decoded letters into words of [`CORE_LENGTH`] letters and decrypts them again
+
+    Stops when a core is not a letter, or when there are not enough letters left to make a word.
+    Returns every level, starting with the input `words`
+*/
+pub fn decrypt_nested(words: &[String], rules: &CoreRules) -> Vec<NestedLevel> {
+    let mut levels = Vec::new();
+    let mut words = words.to_vec();
+    loop {
+        let cores = words
+            .iter()
+            .map(|word| decrypt_word(word, rules))
+            .collect::<Vec<_>>();
+        let level = NestedLevel { words, cores };
+        let letters = level.letters(rules.encoding);
+        levels.push(level);
+
+        let Some(letters) = letters else {
+            return levels;
+        };
+        if letters.len() < *CORE_LENGTHS.start() {
+            return levels;
+        }
+        words = letters
+            .chunks(CORE_LENGTH)
+            .map(|chunk| chunk.iter().copied().collect())
+            .collect();
+        // A short last word is merged into the previous one when possible
+        if let [.., previous, last] = words.as_mut_slice()
+            && !CORE_LENGTHS.contains(&last.len())
+            && CORE_LENGTHS.contains(&(previous.len() + last.len()))
+        {
+            previous.push_str(last);
+            words.pop();
+        }
     }
+}
 
-    let numbers = word
-        .chars()
-        .flat_map(Letter::try_from)
-        .map(Letter::to_num)
-        .collect_array()
-        .ok_or(DecryptError::InputLetter)?;
+/// One level of a nested decryption
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NestedLevel {
+    pub words: Vec<String>,
+    pub cores: Vec<Result<u32, DecryptError>>,
+}
+impl NestedLevel {
+    /// Decoded letters, `None` if a core is not a letter
+    pub fn letters(&self, encoding: LetterEncoding) -> Option<Vec<Letter>> {
+        self.cores
+            .iter()
+            .map(|core| core.as_ref().ok().and_then(|&core| encoding.letter(core)))
+            .collect()
+    }
 
-    decrypt_numbers(numbers)
+    /// Decoded letters, with a `?` for cores that are not a letter
+    pub fn text(&self, encoding: LetterEncoding) -> String {
+        self.cores
+            .iter()
+            .map(|core| {
+                core.as_ref()
+                    .ok()
+                    .and_then(|&core| encoding.letter(core))
+                    .map_or('?', Letter::to_char)
+            })
+            .collect()
+    }
+}
+
+/// Same as [`decrypt_word`], also reporting the arithmetic needed to reach the core
+pub fn decrypt_word_with_arithmetic(word: &str, rules: &CoreRules) -> Result<Core, DecryptError> {
+    let value = decrypt_word(word, rules)?;
+    let numbers = word_to_numbers(word, rules.encoding)?;
+    let arithmetic = arithmetic_needed(&numbers, value, rules);
+    Ok(Core { value, arithmetic })
+}
+
+/// Converts a `word` into its cypher numbers, using the `encoding`
+pub fn word_to_numbers(word: &str, encoding: LetterEncoding) -> Result<Vec<u32>, DecryptError> {
+    word_to_positions(word).map(|positions| {
+        positions
+            .into_iter()
+            .map(|position| encoding.number_at(position))
+            .collect()
+    })
+}
+
+/// Converts a `word` into the positions of its letters in the alphabet
+fn word_to_positions(word: &str) -> Result<Vec<u32>, DecryptError> {
+    if !CORE_LENGTHS.contains(&word.chars().count()) {
+        return Err(DecryptError::InputWordLen);
+    }
+
+    word.chars()
+        .map(|c| Letter::try_from(c).map(Letter::to_num))
+        .collect::<Result<_, _>>()
+        .map_err(|_| DecryptError::InputLetter)
 }
 
 /**
-    Computes the numeric core from the 4 input `numbers`
+    Computes the numeric core from the input `numbers`
+
+    The game uses 4 numbers, but any amount in [`CORE_LENGTHS`] is accepted
 
     # Errors
+    - Invalid amount of numbers
     - No solution found
+    - Division rejected by the `rules`
+    - Overflow, when an intermediate value or the core is too large
+*/
+pub fn decrypt_numbers(numbers: &[u32], rules: &CoreRules) -> Result<u32, DecryptError> {
+    if !CORE_LENGTHS.contains(&numbers.len()) {
+        return Err(DecryptError::InputNumsLen);
+    }
+    let ops = OperationPool::new(rules.operations, numbers.len());
+    let mut best = None;
+    for start in next_numbers(numbers, 0, rules) {
+        let acc = numbers[start].into();
+        if let Some(core) = decrypt_recursive(acc, numbers, 1 << start, ops, rules)? {
+            best = Some(rules.combine.combine(best, core));
+        }
+    }
+    let core = best.ok_or(DecryptError::NoSolution)?;
+    u32::try_from(core).map_err(|_| DecryptError::Overflow)
+}
+
+/**
+    Every completion of the `slots` that decrypts to the `target` core, unknown numbers being `None`
+
+    Unknown numbers are taken from `domain`.
+    Completions are tried in lexicographic order, until every combination was tried or the `limits` are reached
+*/
+pub fn solve_numbers(
+    slots: &[Option<u32>],
+    target: u32,
+    domain: RangeInclusive<u32>,
+    rules: &CoreRules,
+    limits: &SearchLimits,
+) -> NumberSearch {
+    let (first, last) = domain.into_inner();
+    let Some(span) = last.checked_sub(first) else {
+        return NumberSearch {
+            numbers: Vec::new(),
+            stopped: None,
+        };
+    };
+    let base = span as u64 + 1;
+    let unknowns = slots.iter().positions(Option::is_none).collect::<Vec<_>>();
+    let combinations = base.checked_pow(unknowns.len() as u32).unwrap_or(u64::MAX);
+
+    let (numbers, stopped) = search(combinations, limits, |mut index| {
+        let mut numbers = slots
+            .iter()
+            .map(|slot| slot.unwrap_or_default())
+            .collect::<Vec<_>>();
+        for &slot in unknowns.iter().rev() {
+            numbers[slot] = first + (index % base) as u32;
+            index /= base;
+        }
+        let core = decrypt_numbers(&numbers, rules).ok()?;
+        (core == target).then_some(numbers)
+    });
+    NumberSearch { numbers, stopped }
+}
+
+/**
+    Same as [`decrypt_numbers`], also reporting the arithmetic needed to reach the core
+
+    With [`Arithmetic::Rational`] rules, a core that integer arithmetic alone also reaches is reported as [`Arithmetic::Integer`]
 */
-pub fn decrypt_numbers(numbers: [u32; CORE_LENGTH]) -> Result<u32, DecryptError> {
-    let first = numbers[0];
-    let remaining_3 = &numbers[1..];
-    decrypt_recursive(first, remaining_3, Operation::all()).ok_or(DecryptError::NoSolution)
+pub fn decrypt_numbers_with_arithmetic(
+    numbers: &[u32],
+    rules: &CoreRules,
+) -> Result<Core, DecryptError> {
+    let value = decrypt_numbers(numbers, rules)?;
+    let arithmetic = arithmetic_needed(numbers, value, rules);
+    Ok(Core { value, arithmetic })
+}
+
+/// Arithmetic needed to reach the `core` of the `numbers`
+fn arithmetic_needed(numbers: &[u32], core: u32, rules: &CoreRules) -> Arithmetic {
+    let integer_rules = CoreRules {
+        arithmetic: Arithmetic::Integer,
+        ..*rules
+    };
+    match rules.arithmetic == Arithmetic::Integer
+        || decrypt_numbers(numbers, &integer_rules) == Ok(core)
+    {
+        true => Arithmetic::Integer,
+        false => Arithmetic::Rational,
+    }
+}
+
+/// A numeric core, and the arithmetic needed to reach it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Core {
+    pub value: u32,
+    pub arithmetic: Arithmetic,
+}
+/// A core reached with integer arithmetic
+impl From<u32> for Core {
+    fn from(value: u32) -> Self {
+        Core {
+            value,
+            arithmetic: Arithmetic::Integer,
+        }
+    }
+}
+impl Display for Core {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.arithmetic {
+            Arithmetic::Integer => write!(f, "{}", self.value),
+            Arithmetic::Rational => write!(f, "{} (rational)", self.value),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
 pub enum DecryptError {
     #[display("Invalid characters, expected alphabetic words or numbers")]
     InputEmpty,
-    #[display("Invalid length, expected 4 character words")]
+    #[display("Invalid length, expected 3 to 6 character words")]
     InputWordLen,
-    #[display("Invalid length, expected 4 distinct numbers")]
+    #[display("Invalid length, expected 3 to 6 distinct numbers")]
     InputNumsLen,
     #[display("Invalid character, expected alphabetic character")]
     InputLetter,
+    #[display("{_0}")]
+    InputPattern(ParsePatternError),
     #[display("Found words mixed with numbers")]
     InputMixed,
     #[display("No solution found")]
     NoSolution,
+    #[display("No solution found, division with a remainder rejected by the rules")]
+    RejectedDivision,
+    #[display("Overflow, numbers are too large to compute the core")]
+    Overflow,
+}
+impl DecryptError {
+    /// Whether the error comes from a malformed input, rather than from the decryption
+    pub fn is_input(&self) -> bool {
+        matches!(
+            self,
+            Self::InputEmpty
+                | Self::InputWordLen
+                | Self::InputNumsLen
+                | Self::InputLetter
+                | Self::InputPattern(_)
+                | Self::InputMixed
+        )
+    }
+
+    /// Position of the faulty character in its word, counted in characters from 0
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            Self::InputPattern(error) => error.position(),
+            _ => None,
+        }
+    }
+}
+
+/// [`DecryptError`] located on a token of the input
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+#[display("{error} (word {})", index + 1)]
+pub struct InputError {
+    /// Position of the token among the whitespace separated tokens of the input
+    pub index: usize,
+    /// Position of the token in the input, counted in bytes
+    pub span: Range<usize>,
+    #[error(source)]
+    pub error: DecryptError,
+}
+impl InputError {
+    /// Error on the `index`-th whitespace separated token of the `input`, narrowed to the faulty character if it is known
+    pub fn at(input: &str, index: usize, error: DecryptError) -> Self {
+        let token = DecryptInput::token_spans(input)
+            .nth(index)
+            .unwrap_or(input.len()..input.len());
+        let character = error
+            .position()
+            .and_then(|position| input[token.clone()].char_indices().nth(position));
+        let span = match character {
+            Some((offset, c)) => token.start + offset..token.start + offset + c.len_utf8(),
+            None => token,
+        };
+        Self { index, span, error }
+    }
+
+    /// Error on a `span` of the `input`, counted as part of the token it starts in
+    fn spanning(input: &str, span: Range<usize>, error: DecryptError) -> Self {
+        let index = DecryptInput::token_spans(input)
+            .take_while(|token| token.end <= span.start)
+            .count();
+        Self { index, span, error }
+    }
+
+    /**
+        Line of the `input` containing the faulty token, with carets under it
+
+        ```text
+        HEAT TICKETS DATE
+             ^^^^^^^
+        ```
+    */
+    pub fn diagnostic(&self, input: &str) -> String {
+        let start = self.span.start.min(input.len());
+        let line_start = input[..start].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = input[start..]
+            .find('\n')
+            .map_or(input.len(), |index| start + index);
+        let padding = input[line_start..start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let width = input[start..self.span.end.clamp(start, line_end)]
+            .chars()
+            .count();
+        format!(
+            "{}\n{padding}{}",
+            input[line_start..line_end].trim_end_matches('\r'),
+            "^".repeat(width.max(1))
+        )
+    }
 }
 
+/// Characters separating groups of numbers in a [`DecryptInput`]
+pub const GROUP_SEPARATORS: [char; 4] = [',', ';', '|', '\n'];
+
 #[derive(Debug, Clone)]
 pub enum DecryptInput {
     Words(Vec<String>),
-    Numbers([u32; CORE_LENGTH]),
+    /// Groups of numbers, each one decoded independently
+    Numbers(Vec<Vec<u32>>),
+    /// Numbers with unknown slots written `?`, solved with [`solve_numbers`]
+    Partial(Vec<Option<u32>>),
 }
+impl DecryptInput {
+    /// Text of each group of numbers in the `input`, in the order of [`DecryptInput::Numbers`]
+    pub fn number_groups(input: &str) -> impl Iterator<Item = &str> {
+        input
+            .split(GROUP_SEPARATORS)
+            .map(str::trim)
+            .filter(|group| !group.is_empty())
+    }
+
+    /// Byte spans of the whitespace separated tokens of the `input`
+    pub fn token_spans(input: &str) -> impl Iterator<Item = Range<usize>> {
+        input.split_whitespace().map(|token| span_in(input, token))
+    }
+}
+
+/// Byte span of a `part` borrowed from the `input`
+fn span_in(input: &str, part: &str) -> Range<usize> {
+    let start = part.as_ptr() as usize - input.as_ptr() as usize;
+    start..start + part.len()
+}
+/// Errors are located on the faulty token : the first word mixed with numbers, a group of the wrong length or a number too large
 impl FromStr for DecryptInput {
-    type Err = DecryptError;
+    type Err = InputError;
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let mut is_digits = false;
-        let mut is_alphabetic = false;
-        let words = input
-            .split_whitespace()
-            .map(|str| {
-                for c in str.chars() {
-                    if c.is_ascii_digit() {
-                        is_digits = true;
-                    } else if c.is_ascii_alphabetic() {
-                        is_alphabetic = true;
-                    }
-                }
-                str.to_string()
+        let words = input.split_whitespace().collect::<Vec<_>>();
+        let kinds = words
+            .iter()
+            .map(|word| {
+                let is_digits = word.chars().any(|c| c.is_ascii_digit());
+                let is_alphabetic = word.chars().any(|c| c.is_ascii_alphabetic() || c == '?');
+                (is_digits, is_alphabetic)
             })
             .collect::<Vec<_>>();
+        let is_digits = kinds.iter().any(|(is_digits, _)| *is_digits);
+        let is_alphabetic = kinds.iter().any(|(_, is_alphabetic)| *is_alphabetic);
+
+        if words.contains(&"?")
+            && words
+                .iter()
+                .all(|word| *word == "?" || word.chars().all(|c| c.is_ascii_digit()))
+        {
+            if !CORE_LENGTHS.contains(&words.len()) {
+                return Err(InputError::spanning(
+                    input,
+                    span_in(input, input.trim()),
+                    DecryptError::InputNumsLen,
+                ));
+            }
+            return words
+                .iter()
+                .map(|&word| match word {
+                    "?" => Ok(None),
+                    number => number.parse().map(Some).map_err(|_| {
+                        InputError::spanning(input, span_in(input, number), DecryptError::Overflow)
+                    }),
+                })
+                .collect::<Result<_, _>>()
+                .map(DecryptInput::Partial);
+        }
 
         match (is_digits, is_alphabetic) {
-            (true, false) => {
-                if words.len() != CORE_LENGTH {
-                    return Err(DecryptError::InputNumsLen);
-                }
-                words
-                    .into_iter()
-                    .filter_map(|word| word.parse::<u32>().ok())
-                    .collect_array()
-                    .map(DecryptInput::Numbers)
-                    .ok_or(DecryptError::InputNumsLen)
+            (true, false) => Self::number_groups(input)
+                .map(|group| {
+                    let numbers = group.split_whitespace().collect::<Vec<_>>();
+                    if !CORE_LENGTHS.contains(&numbers.len()) {
+                        let span = span_in(input, group);
+                        return Err(InputError::spanning(
+                            input,
+                            span,
+                            DecryptError::InputNumsLen,
+                        ));
+                    }
+                    numbers
+                        .into_iter()
+                        .map(|number| {
+                            number.parse::<u32>().map_err(|error| {
+                                let error = match error.kind() {
+                                    IntErrorKind::PosOverflow => DecryptError::Overflow,
+                                    _ => DecryptError::InputNumsLen,
+                                };
+                                InputError::spanning(input, span_in(input, number), error)
+                            })
+                        })
+                        .collect()
+                })
+                .collect::<Result<_, _>>()
+                .map(DecryptInput::Numbers),
+            (false, true) => Ok(DecryptInput::Words(
+                words.into_iter().map(str::to_string).collect(),
+            )),
+            (false, false) => Err(InputError::spanning(
+                input,
+                0..input.len(),
+                DecryptError::InputEmpty,
+            )),
+            (true, true) => {
+                // A token mixing letters and digits is faulty itself, otherwise the first token unlike the others is
+                let first = kinds.iter().find(|kind| **kind != (false, false));
+                let index = kinds
+                    .iter()
+                    .position(|kind| *kind == (true, true))
+                    .or_else(|| {
+                        kinds
+                            .iter()
+                            .position(|kind| *kind != (false, false) && Some(kind) != first)
+                    })
+                    .unwrap_or_default();
+                Err(InputError::at(input, index, DecryptError::InputMixed))
             }
-            (false, true) => Ok(DecryptInput::Words(words)),
-            (false, false) => Err(DecryptError::InputEmpty),
-            (true, true) => Err(DecryptError::InputMixed),
         }
     }
 }
@@ -103,18 +582,272 @@ impl FromStr for DecryptInput {
 /**
     Brute force decryption
 
-    Pulls the first remaining number and tries to apply it using every possible remaining `Operator`
+    Pulls the next remaining number and tries to apply it using every possible remaining `Operator`.
+    `used` is a bitmask of the `numbers` already pulled.
+
+    Returns the best core according to the `rules`, `None` if every branch is dead.
+    The core may not fit in a `u32` : the caller must check it
 */
-fn decrypt_recursive(acc: u32, numbers: &[u32], ops: Operation) -> Option<u32> {
-    let Some((first, remain)) = numbers.split_first() else {
-        return Some(acc);
+fn decrypt_recursive(
+    acc: Fraction,
+    numbers: &[u32],
+    used: u32,
+    ops: OperationPool,
+    rules: &CoreRules,
+) -> Result<Option<i128>, DecryptError> {
+    if is_complete(numbers, used) {
+        return Ok(acc.to_integer().filter(|core| *core >= 0));
+    }
+    let mut best = None;
+    for index in next_numbers(numbers, used, rules) {
+        for op in ops.iter() {
+            let total = match op.apply(acc, numbers[index].into(), rules) {
+                Ok(total) => total,
+                Err(DeadBranch::RejectedDivision) => return Err(DecryptError::RejectedDivision),
+                Err(DeadBranch::Overflow) => return Err(DecryptError::Overflow),
+                Err(_) => continue,
+            };
+            let used = used | 1 << index;
+            if let Some(core) = decrypt_recursive(total, numbers, used, ops.remove(op), rules)? {
+                best = Some(rules.combine.combine(best, core));
+            }
+        }
+    }
+    Ok(best)
+}
+
+/// Indices of the `numbers` that may be pulled next, `used` being a bitmask of the numbers already pulled
+fn next_numbers(numbers: &[u32], used: u32, rules: &CoreRules) -> impl Iterator<Item = usize> {
+    let available = match rules.reorder {
+        true => numbers.len(),
+        false => 1,
     };
-    ops.iter()
-        .filter_map(|op| {
-            op.apply(acc, *first)
-                .and_then(|total| decrypt_recursive(total, remain, ops.difference(op)))
+    (0..numbers.len())
+        .filter(move |index| used & 1 << index == 0)
+        .take(available)
+}
+
+/// Whether every number has been pulled
+fn is_complete(numbers: &[u32], used: u32) -> bool {
+    used.count_ones() as usize == numbers.len()
+}
+
+/**
+    Records every branch explored while decrypting the input `numbers`
+
+    Same as [`decrypt_numbers`], but keeps the intermediate values and the reason why dead branches were abandoned.
+    An invalid amount of numbers has no branch
+*/
+pub fn explain_numbers(numbers: &[u32], rules: &CoreRules) -> DecryptTrace {
+    let ops = OperationPool::new(rules.operations, numbers.len());
+    let branches = match CORE_LENGTHS.contains(&numbers.len()) {
+        true => next_numbers(numbers, 0, rules)
+            .flat_map(|start| {
+                let acc = numbers[start].into();
+                trace_recursive(acc, numbers, 1 << start, ops, rules)
+            })
+            .collect(),
+        false => Vec::new(),
+    };
+    DecryptTrace {
+        numbers: numbers.to_vec(),
+        branches,
+        core: decrypt_numbers(numbers, rules),
+    }
+}
+
+/**
+    Records every branch explored while decrypting the input `word`
+
+    # Errors
+    - Invalid input
+*/
+pub fn explain_word(word: &str, rules: &CoreRules) -> Result<DecryptTrace, DecryptError> {
+    word_to_numbers(word, rules.encoding).map(|numbers| explain_numbers(&numbers, rules))
+}
+
+/// Every branch explored by the decryption of a group of numbers
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecryptTrace {
+    pub numbers: Vec<u32>,
+    /// Operations applied to the starting number(s)
+    pub branches: Vec<TraceBranch>,
+    /// Final result of the decryption
+    pub core: Result<u32, DecryptError>,
+}
+
+/// A single operation `lhs op rhs` tried during decryption
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceBranch {
+    pub lhs: Fraction,
+    pub op: Operation,
+    pub rhs: Fraction,
+    /// Intermediate value, or the reason why this branch died
+    pub outcome: Result<Fraction, DeadBranch>,
+    /// Operations applied to the intermediate value. Empty for dead branches and final values
+    pub next: Vec<TraceBranch>,
+}
+impl Display for TraceBranch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self { lhs, op, rhs, .. } = self;
+        match self.outcome {
+            Ok(value) => write!(f, "{lhs} {op} {rhs} = {value}"),
+            Err(reason) => write!(f, "{lhs} {op} {rhs} : {reason}"),
+        }
+    }
+}
+
+impl DecryptTrace {
+    /// Every operation order that consumes all the numbers without reaching a dead branch
+    pub fn valid_orders(&self) -> Vec<OperationOrder> {
+        fn collect(
+            branches: &[TraceBranch],
+            len: usize,
+            steps: &mut Vec<Step>,
+            output: &mut Vec<OperationOrder>,
+        ) {
+            for branch in branches {
+                let Ok(value) = branch.outcome else {
+                    continue;
+                };
+                steps.push(Step {
+                    lhs: branch.lhs,
+                    op: branch.op,
+                    rhs: branch.rhs,
+                    value,
+                });
+                match steps.len() == len {
+                    true => output.push(OperationOrder {
+                        steps: steps.clone(),
+                    }),
+                    false => collect(&branch.next, len, steps, output),
+                }
+                steps.pop();
+            }
+        }
+        let mut output = Vec::new();
+        let len = self.numbers.len() - 1;
+        collect(&self.branches, len, &mut Vec::new(), &mut output);
+        output
+    }
+}
+
+/**
+    Computes every core reachable from the input `numbers`
+
+    Unlike [`decrypt_numbers`] which only keeps the minimum,
+    every valid operation order is returned, grouped by resulting core in ascending order.
+    An empty output means there is no solution
+*/
+pub fn decrypt_all(numbers: &[u32], rules: &CoreRules) -> Vec<CoreCandidate> {
+    let mut cores: BTreeMap<u32, Vec<OperationOrder>> = BTreeMap::new();
+    for order in explain_numbers(numbers, rules).valid_orders() {
+        cores.entry(order.core()).or_default().push(order);
+    }
+    cores
+        .into_iter()
+        .map(|(core, orders)| CoreCandidate { core, orders })
+        .collect()
+}
+
+/// A core reachable from a group of numbers, with every operation order producing it
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreCandidate {
+    pub core: u32,
+    pub orders: Vec<OperationOrder>,
+}
+
+/// A complete sequence of valid operations
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationOrder {
+    pub steps: Vec<Step>,
+}
+impl OperationOrder {
+    /// Value produced by the last operation
+    pub fn core(&self) -> u32 {
+        self.steps
+            .last()
+            .and_then(|step| step.value.to_integer())
+            .and_then(|core| u32::try_from(core).ok())
+            .unwrap_or_default()
+    }
+
+    /// Arithmetic needed by the intermediate values
+    pub fn arithmetic(&self) -> Arithmetic {
+        match self.steps.iter().all(|step| step.value.is_integer()) {
+            true => Arithmetic::Integer,
+            false => Arithmetic::Rational,
+        }
+    }
+}
+impl Display for OperationOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.steps.iter().join(", "))
+    }
+}
+
+/// A valid operation `lhs op rhs = value`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub lhs: Fraction,
+    pub op: Operation,
+    pub rhs: Fraction,
+    pub value: Fraction,
+}
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            lhs,
+            op,
+            rhs,
+            value,
+        } = self;
+        write!(f, "{lhs} {op} {rhs} = {value}")
+    }
+}
+
+/// Same recursion as [`decrypt_recursive`], keeping every branch
+fn trace_recursive(
+    acc: Fraction,
+    numbers: &[u32],
+    used: u32,
+    ops: OperationPool,
+    rules: &CoreRules,
+) -> Vec<TraceBranch> {
+    next_numbers(numbers, used, rules)
+        .flat_map(|index| ops.iter().map(move |op| (index, op)))
+        .map(|(index, op)| {
+            let rhs = numbers[index].into();
+            let used = used | 1 << index;
+            let mut outcome = op.apply(acc, rhs, rules);
+            let next = match outcome {
+                Ok(total) if !is_complete(numbers, used) => {
+                    trace_recursive(total, numbers, used, ops.remove(op), rules)
+                }
+                Ok(core) if !core.is_integer() => {
+                    outcome = Err(DeadBranch::FractionalCore);
+                    Vec::new()
+                }
+                Ok(core)
+                    if core
+                        .to_integer()
+                        .and_then(|c| u32::try_from(c).ok())
+                        .is_none() =>
+                {
+                    outcome = Err(DeadBranch::CoreOutOfRange);
+                    Vec::new()
+                }
+                _ => Vec::new(),
+            };
+            TraceBranch {
+                lhs: acc,
+                op,
+                rhs,
+                outcome,
+                next,
+            }
         })
-        .min()
+        .collect()
 }
 
 #[cfg(test)]
@@ -123,22 +856,362 @@ mod tests {
 
     #[test]
     fn known_letters() {
-        assert_eq!(decrypt_word("PEAK").unwrap(), Letter('A').to_num());
-        assert_eq!(decrypt_word("TREE").unwrap(), Letter('B').to_num());
-        assert_eq!(decrypt_word("JOYA").unwrap(), Letter('E').to_num());
-        assert_eq!(decrypt_word("MAIL").unwrap(), Letter('I').to_num());
-        assert_eq!(decrypt_word("ROCK").unwrap(), Letter('K').to_num());
-        assert_eq!(decrypt_word("DATE").unwrap(), Letter('L').to_num());
-        assert_eq!(decrypt_word("WILL").unwrap(), Letter('N').to_num());
-        assert_eq!(decrypt_word("VASE").unwrap(), Letter('O').to_num());
-        assert_eq!(decrypt_word("WELL").unwrap(), Letter('R').to_num());
-        assert_eq!(decrypt_word("PIGS").unwrap(), Letter('S').to_num());
-        assert_eq!(decrypt_word("SAND").unwrap(), Letter('T').to_num());
-        assert_eq!(decrypt_word("CLAM").unwrap(), Letter('W').to_num());
+        let rules = CoreRules::default();
+        assert_eq!(decrypt_word("PEAK", &rules).unwrap(), Letter('A').to_num());
+        assert_eq!(decrypt_word("TREE", &rules).unwrap(), Letter('B').to_num());
+        assert_eq!(decrypt_word("JOYA", &rules).unwrap(), Letter('E').to_num());
+        assert_eq!(decrypt_word("MAIL", &rules).unwrap(), Letter('I').to_num());
+        assert_eq!(decrypt_word("ROCK", &rules).unwrap(), Letter('K').to_num());
+        assert_eq!(decrypt_word("DATE", &rules).unwrap(), Letter('L').to_num());
+        assert_eq!(decrypt_word("WILL", &rules).unwrap(), Letter('N').to_num());
+        assert_eq!(decrypt_word("VASE", &rules).unwrap(), Letter('O').to_num());
+        assert_eq!(decrypt_word("WELL", &rules).unwrap(), Letter('R').to_num());
+        assert_eq!(decrypt_word("PIGS", &rules).unwrap(), Letter('S').to_num());
+        assert_eq!(decrypt_word("SAND", &rules).unwrap(), Letter('T').to_num());
+        assert_eq!(decrypt_word("CLAM", &rules).unwrap(), Letter('W').to_num());
     }
 
     #[test]
     fn known_numbers() {
-        assert_eq!(decrypt_numbers([1000, 200, 11, 2]), Ok(53))
+        let rules = CoreRules::default();
+        assert_eq!(decrypt_numbers(&[1000, 200, 11, 2], &rules), Ok(53))
+    }
+
+    #[test]
+    fn explain() {
+        fn final_values(branches: &[TraceBranch]) -> Vec<Fraction> {
+            branches
+                .iter()
+                .flat_map(|branch| match (&branch.outcome, branch.next.is_empty()) {
+                    (Ok(value), true) => vec![*value],
+                    _ => final_values(&branch.next),
+                })
+                .collect()
+        }
+
+        let rules = CoreRules::default();
+        let trace = explain_numbers(&[1000, 200, 11, 2], &rules);
+        assert_eq!(trace.core, Ok(53));
+        assert_eq!(
+            final_values(&trace.branches).into_iter().min(),
+            Some(Fraction::integer(53))
+        );
+
+        let trace = explain_word("TREE", &rules).unwrap();
+        assert_eq!(trace.branches[0].to_string(), "20 - 18 = 2");
+        assert_eq!(
+            trace.branches[0].next[1].outcome,
+            Err(DeadBranch::NonIntegerDivision)
+        );
+
+        let trace = explain_word("ABCD", &rules).unwrap();
+        assert_eq!(
+            trace.branches[0].to_string(),
+            "1 - 2 : negative subtraction"
+        );
+    }
+
+    #[test]
+    fn all_orders() {
+        let rules = CoreRules::default();
+        let candidates = decrypt_all(
+            &word_to_numbers("PEAK", LetterEncoding::A1Z26).unwrap(),
+            &rules,
+        );
+        let cores = candidates.iter().map(|c| c.core).collect::<Vec<_>>();
+        assert_eq!(cores, [1, 69, 121]);
+        assert_eq!(
+            candidates[0].orders[0].to_string(),
+            "16 - 5 = 11, 11 * 1 = 11, 11 / 11 = 1"
+        );
+        assert_eq!(
+            Some(candidates[0].core),
+            decrypt_word("PEAK", &rules).ok(),
+            "minimum of every order must match the decrypted core"
+        );
+
+        assert!(decrypt_all(&[1, 2, 3, 4], &rules).is_empty());
+        assert_eq!(
+            decrypt_numbers(&[1, 2, 3, 4], &rules),
+            Err(DecryptError::NoSolution)
+        );
+    }
+
+    #[test]
+    fn custom_rules() {
+        use crate::calculator::rules::{CombineRule, DivisionRule};
+
+        let rules = CoreRules::default();
+        let numbers = word_to_numbers("PEAK", LetterEncoding::A1Z26).unwrap();
+        let max = CoreRules {
+            combine: CombineRule::Max,
+            ..Default::default()
+        };
+        assert_eq!(decrypt_numbers(&numbers, &max), Ok(121));
+
+        let addition = CoreRules {
+            operations: "+-*".parse().unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(decrypt_numbers(&[1, 5, 2, 20], &addition), Ok(23));
+        let negative = CoreRules {
+            allow_negative: true,
+            ..addition
+        };
+        assert_eq!(decrypt_numbers(&[1, 5, 2, 20], &negative), Ok(12));
+
+        let floor = CoreRules {
+            division: DivisionRule::Floor,
+            ..Default::default()
+        };
+        assert_eq!(decrypt_numbers(&[7, 2, 4, 1], &rules), Ok(10));
+        assert_eq!(decrypt_numbers(&[7, 2, 4, 1], &floor), Ok(1));
+
+        let reject = CoreRules {
+            division: DivisionRule::Reject,
+            ..Default::default()
+        };
+        assert_eq!(
+            decrypt_numbers(&numbers, &reject),
+            Err(DecryptError::RejectedDivision)
+        );
+
+        let reorder = CoreRules {
+            reorder: true,
+            ..Default::default()
+        };
+        assert_eq!(decrypt_numbers(&[1, 2, 3, 4], &reorder), Ok(1));
+        let candidates = decrypt_all(&[1, 2, 3, 4], &reorder);
+        assert_eq!(candidates[0].core, 1);
+    }
+
+    #[test]
+    fn rational_arithmetic() {
+        let rules = CoreRules::default();
+        let rational = CoreRules {
+            arithmetic: Arithmetic::Rational,
+            ..Default::default()
+        };
+        assert_eq!(
+            decrypt_numbers(&[3, 2, 4, 6], &rules),
+            Err(DecryptError::NoSolution)
+        );
+        assert_eq!(
+            decrypt_numbers_with_arithmetic(&[3, 2, 4, 6], &rational),
+            Ok(Core {
+                value: 0,
+                arithmetic: Arithmetic::Rational
+            })
+        );
+        assert_eq!(
+            decrypt_word_with_arithmetic("PEAK", &rational),
+            Ok(1.into()),
+            "integer arithmetic alone reaches the same core"
+        );
+
+        let candidates = decrypt_all(&[7, 2, 4, 1], &rational);
+        let order = &candidates[1].orders[0];
+        assert_eq!(order.to_string(), "7 / 2 = 7/2, 7/2 * 4 = 14, 14 - 1 = 13");
+        assert_eq!(order.arithmetic(), Arithmetic::Rational);
+    }
+
+    #[test]
+    fn overflow() {
+        use crate::calculator::rules::CombineRule;
+
+        let rules = CoreRules::default();
+        assert_eq!(decrypt_numbers(&[100000, 100000, 100000, 1], &rules), Ok(0));
+        let max = CoreRules {
+            combine: CombineRule::Max,
+            ..Default::default()
+        };
+        assert_eq!(
+            decrypt_numbers(&[100000, 100000, 100000, 1], &max),
+            Err(DecryptError::Overflow)
+        );
+        let multiply = CoreRules {
+            operations: Operation::MUL.into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            decrypt_numbers(&[u32::MAX; 6], &multiply),
+            Err(DecryptError::Overflow)
+        );
+        let power = CoreRules {
+            operations: "^%".parse().unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(
+            decrypt_numbers(&[10, 40, 7], &power),
+            Err(DecryptError::Overflow),
+            "10 ^ 40 % 7 is 4, an overflowing order must not be skipped"
+        );
+        assert!(matches!(
+            "1 2 3 99999999999".parse::<DecryptInput>(),
+            Err(InputError {
+                error: DecryptError::Overflow,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn core_lengths() {
+        let rules = CoreRules::default();
+        assert_eq!(decrypt_numbers(&[10, 2, 5], &rules), Ok(0));
+        assert_eq!(decrypt_word("SEVEN", &rules), Ok(110));
+        assert_eq!(
+            decrypt_all(&[6, 3, 2, 2, 2, 1], &rules)[0].orders[0].to_string(),
+            "6 / 3 = 2, 2 - 2 = 0, 0 * 2 = 0, 0 * 2 = 0, 0 / 1 = 0",
+            "each operation may be used twice with 6 numbers"
+        );
+        assert_eq!(decrypt_word("AB", &rules), Err(DecryptError::InputWordLen));
+        assert_eq!(
+            decrypt_numbers(&[1; 32], &rules),
+            Err(DecryptError::InputNumsLen)
+        );
+        assert!(decrypt_all(&[1; 32], &rules).is_empty());
+        assert!(matches!(
+            "1 2 3 4 5 6 7".parse::<DecryptInput>(),
+            Err(InputError {
+                error: DecryptError::InputNumsLen,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn input_errors() {
+        let input = "HEAT TICKETS DATE";
+        let error = InputError::at(input, 1, DecryptError::InputWordLen);
+        assert_eq!(error.span, 5..12);
+        assert_eq!(error.diagnostic(input), "HEAT TICKETS DATE\n     ^^^^^^^");
+
+        let input = "34 67 22 4\n5 6 7 8 9 10 11";
+        let error = input.parse::<DecryptInput>().unwrap_err();
+        assert_eq!((error.index, error.span.clone()), (4, 11..26));
+        assert_eq!(error.diagnostic(input), "5 6 7 8 9 10 11\n^^^^^^^^^^^^^^^");
+
+        let error = "PIGS SAND 12 DATE".parse::<DecryptInput>().unwrap_err();
+        assert_eq!((error.index, error.error), (2, DecryptError::InputMixed));
+        let error = "A1 B".parse::<DecryptInput>().unwrap_err();
+        assert_eq!((error.index, error.span), (0, 0..2));
+        let error = "B 12 A1".parse::<DecryptInput>().unwrap_err();
+        assert_eq!((error.index, error.span), (2, 5..7));
+    }
+
+    #[test]
+    fn number_groups() {
+        let Ok(DecryptInput::Numbers(groups)) = "34 67 22 4 | 5 6 7, 1 2 3 4;\n".parse() else {
+            panic!("expected groups of numbers");
+        };
+        assert_eq!(
+            groups,
+            [vec![34, 67, 22, 4], vec![5, 6, 7], vec![1, 2, 3, 4]]
+        );
+        assert!(matches!(
+            "34 67 22 4 | 5 6".parse::<DecryptInput>(),
+            Err(InputError {
+                error: DecryptError::InputNumsLen,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn unknown_numbers() {
+        let Ok(DecryptInput::Partial(slots)) = "34 ? 22 4".parse() else {
+            panic!("expected unknown numbers");
+        };
+        assert_eq!(slots, [Some(34), None, Some(22), Some(4)]);
+
+        let rules = CoreRules::default();
+        let search = solve_numbers(&slots, 564, 1..=100, &rules, &SearchLimits::default());
+        assert_eq!(search.stopped, None);
+        assert!(search.numbers.contains(&vec![34, 67, 22, 4]));
+        for numbers in &search.numbers {
+            assert_eq!((numbers[0], numbers[2], numbers[3]), (34, 22, 4));
+            assert_eq!(decrypt_numbers(numbers, &rules), Ok(564));
+        }
+    }
+
+    #[test]
+    fn wildcards() {
+        let rules = CoreRules::default();
+        let limits = SearchLimits::default();
+        let search = decrypt_wildcard_word("pe?K", &rules, &limits).unwrap();
+        assert_eq!(search.stopped, None);
+        let cores = search.cores;
+        let peak = cores.iter().find(|core| core.core == 1).unwrap();
+        assert!(peak.fills.contains(&vec![Letter('A')]));
+        for core in &cores {
+            for fill in &core.fills {
+                let word = format!("PE{}K", fill[0]);
+                assert_eq!(decrypt_word(&word, &rules), Ok(core.core));
+            }
+        }
+        let fills = cores.iter().map(|core| core.fills.len()).sum::<usize>();
+        assert!(fills <= 26);
+
+        let cores = decrypt_wildcard_word("?E[AO]K", &rules, &limits)
+            .unwrap()
+            .cores;
+        assert!(
+            cores
+                .iter()
+                .all(|core| core.fills.iter().all(|f| f.len() == 2))
+        );
+        assert!(has_wildcards("PE?K") && !has_wildcards("PEAK"));
+        assert!(matches!(
+            "PE?K ????".parse::<DecryptInput>(),
+            Ok(DecryptInput::Words(_))
+        ));
+        assert_eq!(
+            decrypt_wildcard_word("P?", &rules, &limits),
+            Err(DecryptError::InputWordLen)
+        );
+        let input = "PEAK A]B[";
+        let error = decrypt_wildcard_word("A]B[", &rules, &limits).unwrap_err();
+        assert_eq!(
+            error,
+            DecryptError::InputPattern(ParsePatternError::Unexpected {
+                symbol: ']',
+                position: 1
+            })
+        );
+        assert_eq!(InputError::at(input, 1, error).span, 6..7);
+
+        let limits = SearchLimits {
+            max_results: 10,
+            ..Default::default()
+        };
+        let search = decrypt_wildcard_word("?????", &rules, &limits).unwrap();
+        assert_eq!(search.stopped, Some(SearchStop::ResultCap));
+        let fills = search
+            .cores
+            .iter()
+            .map(|core| core.fills.len())
+            .sum::<usize>();
+        assert_eq!(fills, 10);
+    }
+
+    #[test]
+    fn nested() {
+        let rules = CoreRules::default();
+        let words = ["SAND", "WELL", "JOYA", "JOYA"].map(String::from);
+        let levels = decrypt_nested(&words, &rules);
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[0].text(rules.encoding), "TREE");
+        assert_eq!(levels[1].words, ["TREE"]);
+        assert_eq!(levels[1].text(rules.encoding), "B");
+
+        // 5 letters are regrouped into a single word
+        let words = ["SAND", "WELL", "JOYA", "JOYA", "PIGS"].map(String::from);
+        let levels = decrypt_nested(&words, &rules);
+        assert_eq!(levels[1].words, ["TREES"]);
+
+        let levels = decrypt_nested(&["PE?K".to_string()], &rules);
+        assert_eq!(levels.len(), 1);
+        assert_eq!(levels[0].text(rules.encoding), "?");
     }
 }
@@ -0,0 +1,193 @@
+use std::{cmp::Reverse, collections::HashMap, path::Path, str::FromStr};
+
+use crate::calculator::Letter;
+
+/// English word list embedded in the program
+#[cfg(feature = "english-dictionary")]
+const ENGLISH: &str = include_str!("../../dictionaries/english.txt");
+
+/**
+    Set of real words, used to sort useful cypher words from random letters
+
+    Word lists are plain text, one word per line, optionally followed by its count in a text corpus : `DATE 1520`.
+    Words without a count have a count of 1.
+    Empty lines, lines starting with `#` and words containing other characters than letters are ignored
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dictionary {
+    /// Count of each word
+    words: HashMap<Vec<Letter>, u64>,
+    /// Whether a word came with its count, without which every word is as frequent
+    counted: bool,
+}
+impl Dictionary {
+    /// Reads a word list
+    pub fn parse(text: &str) -> Self {
+        let words = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let word: Option<Vec<_>> =
+                    fields.next()?.chars().map(Letter::try_from_char).collect();
+                let count = match fields.next() {
+                    None => None,
+                    Some(count) => Some(count.parse().ok()?),
+                };
+                Some((word?, count))
+            });
+        let mut dictionary = Self::default();
+        for (word, count) in words {
+            dictionary.counted |= count.is_some();
+            dictionary.add(word, count.unwrap_or(1));
+        }
+        dictionary
+    }
+
+    /// Reads a word list file
+    pub fn load(path: &Path) -> Result<Self, DictionaryError> {
+        std::fs::read_to_string(path)
+            .map(|text| Self::parse(&text))
+            .map_err(|source| DictionaryError {
+                path: path.display().to_string(),
+                source,
+            })
+    }
+
+    /// Word list embedded in the program, `None` if it was built without the `english-dictionary` feature
+    pub fn english() -> Option<Self> {
+        #[cfg(feature = "english-dictionary")]
+        return Some(Self::parse(ENGLISH));
+        #[cfg(not(feature = "english-dictionary"))]
+        None
+    }
+
+    /// Adds the words of an other dictionary, summing the counts of words in both
+    pub fn merge(&mut self, other: Self) {
+        self.counted |= other.counted;
+        for (word, count) in other.words {
+            self.add(word, count);
+        }
+    }
+
+    fn add(&mut self, word: Vec<Letter>, count: u64) {
+        let total = self.words.entry(word).or_default();
+        *total = total.saturating_add(count);
+    }
+
+    /// Every word, in no particular order
+    pub fn words(&self) -> impl Iterator<Item = &[Letter]> {
+        self.words.keys().map(Vec::as_slice)
+    }
+
+    pub fn contains(&self, word: &[Letter]) -> bool {
+        self.words.contains_key(word)
+    }
+
+    /// Whether the word list gave counts, needed by [`SortOrder::Frequency`]
+    pub fn has_counts(&self) -> bool {
+        self.counted
+    }
+
+    /// Count of the `word`, 0 if it is not in the dictionary
+    pub fn frequency(&self, word: &[Letter]) -> u64 {
+        self.words.get(word).copied().unwrap_or_default()
+    }
+}
+
+/// Order of encryption results
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum SortOrder {
+    /// Alphabetical order
+    #[default]
+    #[display("alpha")]
+    Alpha,
+    /// Most frequent dictionary words first, then alphabetical order
+    #[display("frequency")]
+    Frequency,
+}
+impl SortOrder {
+    /// Sorts the `words`, using the counts of the `dictionary` for [`SortOrder::Frequency`]
+    pub fn sort(self, words: &mut [Vec<Letter>], dictionary: Option<&Dictionary>) {
+        words.sort();
+        if let (Self::Frequency, Some(dictionary)) = (self, dictionary) {
+            words.sort_by_key(|word| Reverse(dictionary.frequency(word)));
+        }
+    }
+
+    /// The other order
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Alpha => Self::Frequency,
+            Self::Frequency => Self::Alpha,
+        }
+    }
+}
+impl FromStr for SortOrder {
+    type Err = ParseSortError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "alpha" => Ok(Self::Alpha),
+            "frequency" => Ok(Self::Frequency),
+            _ => Err(ParseSortError),
+        }
+    }
+}
+
+#[derive(Debug, derive_more::Display, derive_more::Error)]
+#[display("Invalid sort order, expected one of : alpha, frequency")]
+pub struct ParseSortError;
+
+#[derive(Debug, derive_more::Display, derive_more::Error)]
+#[display("Can't read dictionary {path}: {source}")]
+pub struct DictionaryError {
+    path: String,
+    source: std::io::Error,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_lists() {
+        let dictionary = Dictionary::parse("date\n# comment\n\n  Head \nit's\nROAD\n");
+        assert_eq!(dictionary.words.len(), 3);
+        let word = |s: &str| {
+            s.chars()
+                .filter_map(Letter::try_from_char)
+                .collect::<Vec<_>>()
+        };
+        assert!(dictionary.contains(&word("DATE")));
+        assert!(dictionary.contains(&word("HEAD")));
+        assert!(!dictionary.contains(&word("ITS")));
+        assert!(!dictionary.has_counts());
+        assert!(Dictionary::load(Path::new("missing file.txt")).is_err());
+    }
+
+    #[test]
+    fn frequencies() {
+        let mut dictionary = Dictionary::parse("ROAD 20\nDATE 350\nHEAD\nJAIL x\n");
+        dictionary.merge(Dictionary::parse("HEAD 2"));
+        let word = |s: &str| {
+            s.chars()
+                .filter_map(Letter::try_from_char)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(dictionary.frequency(&word("HEAD")), 3);
+        assert!(!dictionary.contains(&word("JAIL")));
+        assert!(dictionary.has_counts());
+
+        let mut frequency = ["ZZZZ", "HEAD", "ROAD", "AAAA", "DATE"].map(word);
+        SortOrder::Frequency.sort(&mut frequency, Some(&dictionary));
+        assert_eq!(
+            frequency,
+            ["DATE", "ROAD", "HEAD", "AAAA", "ZZZZ"].map(word)
+        );
+        let mut alpha = frequency.clone();
+        SortOrder::Alpha.sort(&mut alpha, Some(&dictionary));
+        assert_eq!(alpha, ["AAAA", "DATE", "HEAD", "ROAD", "ZZZZ"].map(word));
+        assert_ne!(frequency, alpha);
+    }
+}
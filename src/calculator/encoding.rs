@@ -0,0 +1,137 @@
+use std::{fmt::Display, ops::RangeInclusive, str::FromStr};
+
+use crate::calculator::{ALPHABET, Letter, rules::ParseRuleError};
+
+/// Keys of a phone keypad, for each letter of the alphabet
+const KEYPAD: [u32; 26] = [
+    2, 2, 2, 3, 3, 3, 4, 4, 4, 5, 5, 5, 6, 6, 6, 7, 7, 7, 7, 8, 8, 8, 9, 9, 9, 9,
+];
+
+/**
+    How letters are converted to cypher numbers, and cores back to letters
+
+    The game uses [`LetterEncoding::A1Z26`].
+    Letters are stored by their position in the alphabet, see [`Letter::to_num`]
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LetterEncoding {
+    /// A is 1, Z is 26
+    #[default]
+    A1Z26,
+    /// A is 0, Z is 25
+    A0Z25,
+    /// Z is 1, A is 26
+    Z1A26,
+    /// Key of the letter on a phone keypad : ABC is 2, WXYZ is 9.
+    /// Several letters share a number, a core is read as the first letter of its key
+    Keypad,
+}
+impl LetterEncoding {
+    pub const ALL: [Self; 4] = [Self::A1Z26, Self::A0Z25, Self::Z1A26, Self::Keypad];
+
+    /// Cypher number of a `letter`
+    pub fn number(self, letter: Letter) -> u32 {
+        self.number_at(letter.to_num())
+    }
+
+    /// Cypher number of the letter at a `position` in the alphabet, `A` being 1
+    pub fn number_at(self, position: u32) -> u32 {
+        match self {
+            Self::A1Z26 => position,
+            Self::A0Z25 => position - 1,
+            Self::Z1A26 => ALPHABET.end() + 1 - position,
+            Self::Keypad => KEYPAD[position as usize - 1],
+        }
+    }
+
+    /// Cypher numbers of the letters of the alphabet
+    pub fn numbers(self) -> RangeInclusive<u32> {
+        match self {
+            Self::A1Z26 | Self::Z1A26 => ALPHABET,
+            Self::A0Z25 => ALPHABET.start() - 1..=ALPHABET.end() - 1,
+            Self::Keypad => KEYPAD[0]..=KEYPAD[KEYPAD.len() - 1],
+        }
+    }
+
+    /// Word converted to the cypher `numbers`, `None` if one of them is not a letter
+    pub fn word(self, numbers: &[u32]) -> Option<Vec<Letter>> {
+        numbers.iter().map(|&number| self.letter(number)).collect()
+    }
+
+    /// Letter converted to the cypher `number`, the first one if several letters share it
+    pub fn letter(self, number: u32) -> Option<Letter> {
+        match self {
+            Self::A1Z26 => Letter::try_from_num(number),
+            Self::A0Z25 => number.checked_add(1).and_then(Letter::try_from_num),
+            Self::Z1A26 => (ALPHABET.end() + 1)
+                .checked_sub(number)
+                .and_then(Letter::try_from_num),
+            Self::Keypad => {
+                let position = KEYPAD.iter().position(|&key| key == number)?;
+                Letter::try_from_num(position as u32 + 1)
+            }
+        }
+    }
+}
+impl Display for LetterEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::A1Z26 => write!(f, "a1z26"),
+            Self::A0Z25 => write!(f, "a0z25"),
+            Self::Z1A26 => write!(f, "z1a26"),
+            Self::Keypad => write!(f, "keypad"),
+        }
+    }
+}
+impl FromStr for LetterEncoding {
+    type Err = ParseRuleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|encoding| encoding.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or(ParseRuleError {
+                expected: "a1z26, a0z25, z1a26, keypad",
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::{
+        decryptor::decrypt_word, encryptor::encrypt_letter, pattern::Pattern, rules::CoreRules,
+        search::SearchLimits,
+    };
+
+    #[test]
+    fn encodings() {
+        let letter = |c| Letter::try_from_char(c).unwrap();
+        assert_eq!(LetterEncoding::A0Z25.number(letter('A')), 0);
+        assert_eq!(LetterEncoding::Z1A26.number(letter('A')), 26);
+        assert_eq!(LetterEncoding::Keypad.number(letter('S')), 7);
+        assert_eq!(LetterEncoding::Keypad.letter(7), Some(letter('P')));
+        assert_eq!(LetterEncoding::Keypad.letter(1), None);
+        assert_eq!(LetterEncoding::A0Z25.numbers(), 0..=25);
+        assert_eq!(
+            "Keypad".parse::<LetterEncoding>().unwrap(),
+            LetterEncoding::Keypad
+        );
+
+        for encoding in LetterEncoding::ALL {
+            let rules = CoreRules {
+                encoding,
+                ..Default::default()
+            };
+            let target = letter('L');
+            let words = encrypt_letter(target, &Pattern::any(3), &rules, &SearchLimits::default())
+                .unwrap()
+                .words;
+            assert!(!words.is_empty(), "{encoding}");
+            for word in words.iter().step_by(97) {
+                let word = word.iter().copied().collect::<String>();
+                let core = decrypt_word(&word, &rules).unwrap();
+                assert_eq!(core, encoding.number(target), "{encoding} {word}");
+            }
+        }
+    }
+}
@@ -1,49 +1,153 @@
-use rayon::prelude::*;
+use std::ops::RangeInclusive;
 
-use crate::calculator::{ALPHABET, CORE_LENGTH, Letter, decryptor::decrypt_numbers};
+use crate::calculator::{
+    ALPHABET, CORE_LENGTHS, Letter,
+    decryptor::{decrypt_numbers, solve_numbers},
+    encoding::LetterEncoding,
+    pattern::Pattern,
+    rules::CoreRules,
+    search::{NumberSearch, SearchLimits, SearchStop, WordSearch, search},
+    table::{CoreTable, MAX_CORE_LENGTH},
+};
 
 /**
-   Brute force encryption
-   Tries every posible 4-letter combination that reaches the input character
-   `input` may be uppercase or lowercase
+   Every word matching the `pattern` that reaches the input letter under the given `rules`, in alphabetical order
+
+   The letter and the words are converted to numbers with the [`LetterEncoding`] of the `rules`
+
+   Words up to [`MAX_TABLE_LENGTH`](crate::calculator::table::MAX_TABLE_LENGTH) letters are looked up in a [`CoreTable`], built on first use.
+   Other words are brute forced, only trying the words matching the `pattern` : each extra wildcard multiplies the computation time by 26.
+   Either way, the words stop at the `limits`
+
+   # Errors
+   - Word length outside of [`CORE_LENGTHS`]
 */
-pub fn encrypt_letter(letter: Letter) -> Vec<[Letter; CORE_LENGTH]> {
-    let all_combinations = ALPHABET.flat_map(move |a| {
-        ALPHABET.flat_map(move |b| ALPHABET.flat_map(move |c| ALPHABET.map(move |d| [a, b, c, d])))
+pub fn encrypt_letter(
+    letter: Letter,
+    pattern: &Pattern,
+    rules: &CoreRules,
+    limits: &SearchLimits,
+) -> Result<WordSearch, EncryptError> {
+    let length = pattern.len();
+    if !CORE_LENGTHS.contains(&length) {
+        return Err(EncryptError::InputLen);
+    }
+    // Building a table is only worth it if every word is needed
+    let table = match pattern.is_any() {
+        true => CoreTable::get(length, rules),
+        false => CoreTable::cached(length, rules),
+    };
+    let target = rules.encoding.number(letter);
+    if let Some(table) = table {
+        let mut words = table.words(target).filter(|word| pattern.matches(word));
+        let found = words.by_ref().take(limits.max_results).collect();
+        let stopped = words.next().map(|_| SearchStop::ResultCap);
+        return Ok(WordSearch {
+            words: found,
+            stopped,
+        });
+    }
+
+    let (words, stopped) = search(pattern.combinations(), limits, |index| {
+        let mut positions = [0; MAX_CORE_LENGTH];
+        let positions = &mut positions[..length];
+        pattern.write_numbers(index, positions);
+        let mut numbers = [0; MAX_CORE_LENGTH];
+        let numbers = &mut numbers[..length];
+        for (number, &position) in numbers.iter_mut().zip(positions.iter()) {
+            *number = rules.encoding.number_at(position);
+        }
+        let core = decrypt_numbers(numbers, rules).ok()?;
+        if core != target {
+            return None;
+        }
+        positions
+            .iter()
+            .map(|&position| Letter::try_from_num(position))
+            .collect()
     });
+    Ok(WordSearch { words, stopped })
+}
 
-    let mut output = all_combinations
-        .par_bridge()
-        .filter_map(|numbers| {
-            let core = decrypt_numbers(numbers).ok()?;
-            (core == letter.to_num()).then_some(numbers)
-        })
-        .filter_map(|[a, b, c, d]| {
-            Some([
-                Letter::try_from_num(a)?,
-                Letter::try_from_num(b)?,
-                Letter::try_from_num(c)?,
-                Letter::try_from_num(d)?,
-            ])
-        })
-        .collect::<Vec<_>>();
-
-    output.sort();
-
-    output
+/**
+   Every group of `length` numbers taken from `domain` that reaches the `target` core under the given `rules`
+
+   Groups are tried in lexicographic order with [`solve_numbers`], until every combination was tried or the `limits` are reached.
+   Letters of words up to [`MAX_TABLE_LENGTH`](crate::calculator::table::MAX_TABLE_LENGTH) are looked up in a [`CoreTable`]
+
+   # Errors
+   - `length` outside of [`CORE_LENGTHS`]
+*/
+pub fn encrypt_number(
+    target: u32,
+    domain: RangeInclusive<u32>,
+    length: usize,
+    rules: &CoreRules,
+    limits: &SearchLimits,
+) -> Result<NumberSearch, EncryptError> {
+    if !CORE_LENGTHS.contains(&length) {
+        return Err(EncryptError::InputLen);
+    }
+    // Numbers of the table are the positions of its letters in the alphabet
+    let table_rules = CoreRules {
+        encoding: LetterEncoding::A1Z26,
+        ..*rules
+    };
+    if domain == ALPHABET
+        && let Some(table) = CoreTable::get(length, &table_rules)
+    {
+        let mut words = table.words(target);
+        let numbers = words
+            .by_ref()
+            .take(limits.max_results)
+            .map(|word| word.into_iter().map(Letter::to_num).collect())
+            .collect();
+        let stopped = words.next().map(|_| SearchStop::ResultCap);
+        return Ok(NumberSearch { numbers, stopped });
+    }
+
+    Ok(solve_numbers(
+        &vec![None; length],
+        target,
+        domain,
+        rules,
+        limits,
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum EncryptError {
+    #[display("Empty input")]
+    InputEmpty,
+    #[display("Invalid input: expected alphabetic letters separated by spaces, found '{_0}'")]
+    InputLetter(#[error(not(source))] char),
+    #[display("Invalid length, expected 3 to 6 letters or numbers")]
+    InputLen,
+    #[display("No cypher word reaches the letter {_0}")]
+    NoCandidate(#[error(not(source))] Letter),
+    #[display("Not enough distinct cypher words for every letter {_0}")]
+    NotEnoughCandidates(#[error(not(source))] Letter),
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
 
-    use itertools::Itertools;
+    use crate::calculator::CORE_LENGTH;
 
     use super::*;
 
     #[test]
     fn known_letters() {
         let letter = Letter::try_from('L').unwrap();
-        let values = encrypt_letter(letter);
+        let values = encrypt_letter(
+            letter,
+            &Pattern::any(CORE_LENGTH),
+            &CoreRules::default(),
+            &SearchLimits::default(),
+        )
+        .unwrap()
+        .words;
         #[allow(non_snake_case)]
         let known_L = [
             ['D', 'A', 'T', 'E'],
@@ -54,9 +158,121 @@ mod tests {
             let word = word
                 .into_iter()
                 .filter_map(|c| Letter::try_from(c).ok())
-                .collect_array()
-                .unwrap();
+                .collect::<Vec<_>>();
             assert!(values.contains(&word))
         }
     }
+
+    #[test]
+    fn short_words() {
+        let letter = Letter::try_from('C').unwrap();
+        let values = encrypt_letter(
+            letter,
+            &Pattern::any(3),
+            &CoreRules::default(),
+            &SearchLimits::default(),
+        )
+        .unwrap()
+        .words;
+        assert!(!values.is_empty());
+        assert!(values.iter().all(|word| word.len() == 3));
+
+        let pattern = "?[AEIOU]?".parse().unwrap();
+        let matching = encrypt_letter(
+            letter,
+            &pattern,
+            &CoreRules::default(),
+            &SearchLimits::default(),
+        )
+        .unwrap()
+        .words;
+        assert!(!matching.is_empty());
+        let filtered = values.into_iter().filter(|word| pattern.matches(word));
+        assert!(filtered.eq(matching));
+    }
+
+    #[test]
+    fn limited_letters() {
+        let letter = Letter::try_from('C').unwrap();
+        let limits = SearchLimits {
+            max_results: 3,
+            ..Default::default()
+        };
+        let search =
+            encrypt_letter(letter, &Pattern::any(3), &CoreRules::default(), &limits).unwrap();
+        assert_eq!(search.stopped, Some(SearchStop::ResultCap));
+        assert_eq!(search.words.len(), 3);
+
+        let limits = SearchLimits {
+            time_budget: Duration::ZERO,
+            ..Default::default()
+        };
+        let search =
+            encrypt_letter(letter, &Pattern::any(6), &CoreRules::default(), &limits).unwrap();
+        assert_eq!(search.stopped, Some(SearchStop::TimeBudget));
+
+        let search = encrypt_letter(letter, &Pattern::any(7), &CoreRules::default(), &limits);
+        assert_eq!(search, Err(EncryptError::InputLen));
+    }
+
+    #[test]
+    fn numeric_targets() {
+        let rules = CoreRules::default();
+        let limits = SearchLimits {
+            max_results: 3,
+            ..Default::default()
+        };
+        let search = encrypt_number(53, 1..=100, 3, &rules, &limits).unwrap();
+        assert_eq!(search.stopped, Some(SearchStop::ResultCap));
+        assert_eq!(search.numbers.len(), 3);
+        for numbers in search.numbers {
+            assert_eq!(decrypt_numbers(&numbers, &rules), Ok(53));
+        }
+
+        let limits = SearchLimits {
+            time_budget: Duration::ZERO,
+            ..Default::default()
+        };
+        let search = encrypt_number(53, 1..=100, 4, &rules, &limits).unwrap();
+        assert_eq!(search.stopped, Some(SearchStop::TimeBudget));
+
+        let letter = Letter::try_from('L').unwrap();
+        let words = encrypt_letter(letter, &Pattern::any(3), &rules, &SearchLimits::default())
+            .unwrap()
+            .words;
+        let search = encrypt_number(12, ALPHABET, 3, &rules, &SearchLimits::default()).unwrap();
+        assert_eq!(search.stopped, None);
+        assert_eq!(search.numbers.len(), words.len());
+    }
+
+    #[test]
+    fn encoded_targets() {
+        let encoding = LetterEncoding::A0Z25;
+        let rules = CoreRules {
+            encoding,
+            ..Default::default()
+        };
+        let letter = Letter::try_from('L').unwrap();
+        let words = encrypt_letter(letter, &Pattern::any(3), &rules, &SearchLimits::default())
+            .unwrap()
+            .words;
+        let target = encoding.number(letter);
+        let search = encrypt_number(
+            target,
+            encoding.numbers(),
+            3,
+            &rules,
+            &SearchLimits::default(),
+        )
+        .unwrap();
+        assert_eq!(search.stopped, None);
+        let mut found = search
+            .numbers
+            .iter()
+            .map(|numbers| encoding.word(numbers).unwrap())
+            .collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(found, words);
+        assert_eq!(encoding.word(&[26, 0, 1]), None);
+    }
 }
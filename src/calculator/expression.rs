@@ -0,0 +1,283 @@
+use std::{
+    fmt::Display,
+    iter::{Enumerate, Peekable},
+    str::{Chars, FromStr},
+};
+
+use crate::calculator::{
+    CORE_LENGTHS, DeadBranch,
+    decryptor::Step,
+    fraction::Fraction,
+    operator::{Operation, OperationPool},
+    rules::CoreRules,
+};
+
+/**
+    Arithmetic expression written by hand, like `((34-4)*22)/67`
+
+    Numbers are whole numbers, combined with the symbols of the [`OPERATORS`](crate::calculator::operator::OPERATORS) and parentheses.
+    Operators with a higher precedence are applied first, like multiplication before addition, then from left to right
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expression {
+    Number(u32),
+    Binary {
+        lhs: Box<Expression>,
+        op: Operation,
+        rhs: Box<Expression>,
+    },
+}
+impl Expression {
+    /**
+        Computes the expression with the same validity rules as the decryption
+
+        Every operation is applied in evaluation order, until one of them is a dead branch
+    */
+    pub fn evaluate(&self, rules: &CoreRules) -> Evaluation {
+        fn recurse(
+            expression: &Expression,
+            rules: &CoreRules,
+            steps: &mut Vec<Step>,
+        ) -> Result<Fraction, DeadBranch> {
+            match expression {
+                Expression::Number(number) => Ok(Fraction::from(*number)),
+                Expression::Binary { lhs, op, rhs } => {
+                    let lhs = recurse(lhs, rules, steps)?;
+                    let rhs = recurse(rhs, rules, steps)?;
+                    let value = op.apply(lhs, rhs, rules)?;
+                    steps.push(Step {
+                        lhs,
+                        op: *op,
+                        rhs,
+                        value,
+                    });
+                    Ok(value)
+                }
+            }
+        }
+        let mut steps = Vec::new();
+        let value = recurse(self, rules, &mut steps);
+        Evaluation { steps, value }
+    }
+
+    /// Numbers of the expression, from left to right
+    pub fn numbers(&self) -> Vec<u32> {
+        match self {
+            Self::Number(number) => vec![*number],
+            Self::Binary { lhs, rhs, .. } => {
+                let mut numbers = lhs.numbers();
+                numbers.extend(rhs.numbers());
+                numbers
+            }
+        }
+    }
+
+    /**
+        Checks that the expression is one of the operation orders tried by the decryption
+
+        The first number starts the computation, then each operation combines the result so far with the next number.
+        Operations must be available in the `rules`, each one being used as many times as the decryption allows
+    */
+    pub fn check_order(&self, rules: &CoreRules) -> Result<(), NotAnOrder> {
+        let mut ops = Vec::new();
+        let mut expression = self;
+        while let Self::Binary { lhs, op, rhs } = expression {
+            if !matches!(**rhs, Self::Number(_)) {
+                return Err(NotAnOrder::Nested);
+            }
+            ops.push(*op);
+            expression = lhs;
+        }
+        let len = ops.len() + 1;
+        if !CORE_LENGTHS.contains(&len) {
+            return Err(NotAnOrder::Length(len));
+        }
+        let mut pool = OperationPool::new(rules.operations, len);
+        for op in ops.into_iter().rev() {
+            if !pool.contains(op) {
+                return Err(NotAnOrder::Operation(op));
+            }
+            pool = pool.remove(op);
+        }
+        Ok(())
+    }
+}
+/// Writes the expression with its operators spaced out, every inner operation in parentheses
+impl Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let operand = |expression: &Self| match expression {
+            Self::Number(number) => number.to_string(),
+            Self::Binary { .. } => format!("({expression})"),
+        };
+        match self {
+            Self::Number(number) => write!(f, "{number}"),
+            Self::Binary { lhs, op, rhs } => write!(f, "{} {op} {}", operand(lhs), operand(rhs)),
+        }
+    }
+}
+impl FromStr for Expression {
+    type Err = ParseExpressionError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            chars: s.chars().enumerate().peekable(),
+        };
+        let expression = parser.binary(1)?;
+        match parser.next() {
+            None => Ok(expression),
+            Some((position, symbol)) => Err(ParseExpressionError::Unexpected { symbol, position }),
+        }
+    }
+}
+
+/// Recursive descent over the characters of an expression, skipping whitespace
+struct Parser<'a> {
+    /// Characters with their position, counted in characters
+    chars: Peekable<Enumerate<Chars<'a>>>,
+}
+impl Parser<'_> {
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        self.peek();
+        self.chars.next()
+    }
+
+    /// Operations of `precedence` or higher, the ones of the same precedence being applied from left to right
+    fn binary(&mut self, precedence: u8) -> Result<Expression, ParseExpressionError> {
+        let highest = Operation::all().map(Operation::precedence).max();
+        if Some(precedence) > highest {
+            return self.operand();
+        }
+        let mut expression = self.binary(precedence + 1)?;
+        while let Some(op) = self
+            .peek()
+            .and_then(Operation::from_symbol)
+            .filter(|op| op.precedence() == precedence)
+        {
+            self.next();
+            expression = Expression::Binary {
+                lhs: Box::new(expression),
+                op,
+                rhs: Box::new(self.binary(precedence + 1)?),
+            };
+        }
+        Ok(expression)
+    }
+
+    /// A number, or an expression in parentheses
+    fn operand(&mut self) -> Result<Expression, ParseExpressionError> {
+        match self.next() {
+            None => Err(ParseExpressionError::End),
+            Some((_, '(')) => {
+                let expression = self.binary(1)?;
+                match self.next() {
+                    Some((_, ')')) => Ok(expression),
+                    Some((position, symbol)) => {
+                        Err(ParseExpressionError::Unexpected { symbol, position })
+                    }
+                    None => Err(ParseExpressionError::End),
+                }
+            }
+            Some((_, c)) if c.is_ascii_digit() => {
+                let mut number = c.to_digit(10).unwrap_or_default();
+                while let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                    number = number
+                        .checked_mul(10)
+                        .and_then(|number| number.checked_add(c.to_digit(10).unwrap_or_default()))
+                        .ok_or(ParseExpressionError::Overflow)?;
+                }
+                Ok(Expression::Number(number))
+            }
+            Some((position, symbol)) => Err(ParseExpressionError::Unexpected { symbol, position }),
+        }
+    }
+}
+
+/// Malformed [`Expression`], positions being counted in characters from 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum ParseExpressionError {
+    #[display("Invalid expression, unexpected `{symbol}` at character {}", position + 1)]
+    Unexpected { symbol: char, position: usize },
+    #[display("Invalid expression, unexpected end")]
+    End,
+    #[display("Invalid expression, number too large")]
+    Overflow,
+}
+
+/// Operations applied by [`Expression::evaluate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evaluation {
+    /// Valid operations, in the order they were applied
+    pub steps: Vec<Step>,
+    /// Value of the expression, or the reason why its last operation is a dead branch
+    pub value: Result<Fraction, DeadBranch>,
+}
+
+/// Why an expression is not one of the operation orders tried by the decryption
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum NotAnOrder {
+    #[display("operations must combine the result so far with the next number")]
+    Nested,
+    #[display("{_0} numbers, cores use 3 to 6")]
+    Length(usize),
+    #[display("operation {_0} is not available, or used too many times")]
+    Operation(Operation),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expressions() {
+        let rules = CoreRules::default();
+        let expression = "((34-4)*22)/ 67".parse::<Expression>().unwrap();
+        assert_eq!(expression.to_string(), "((34 - 4) * 22) / 67");
+        assert_eq!(expression.numbers(), [34, 4, 22, 67]);
+        assert_eq!(expression.check_order(&rules), Ok(()));
+        let evaluation = expression.evaluate(&rules);
+        assert_eq!(evaluation.steps.len(), 2);
+        assert_eq!(evaluation.value, Err(DeadBranch::NonIntegerDivision));
+
+        let expression = "34 - 67 * 22 / 4".parse::<Expression>().unwrap();
+        assert_eq!(expression.to_string(), "34 - ((67 * 22) / 4)");
+        assert_eq!(expression.check_order(&rules), Err(NotAnOrder::Nested));
+        assert_eq!(
+            expression.evaluate(&rules).value,
+            Err(DeadBranch::NonIntegerDivision)
+        );
+
+        let expression = "(34 * 67 - 22) / 4".parse::<Expression>().unwrap();
+        assert_eq!(expression.evaluate(&rules).value, Ok(564.into()));
+        assert_eq!(expression.check_order(&rules), Ok(()));
+        let expression = "34 * 67 * 22 / 4".parse::<Expression>().unwrap();
+        assert_eq!(
+            expression.check_order(&rules),
+            Err(NotAnOrder::Operation(Operation::MUL))
+        );
+
+        let expression = "34 & 4 % 5 ^ 2".parse::<Expression>().unwrap();
+        assert_eq!(expression.to_string(), "(34 & 4) % (5 ^ 2)");
+        assert_eq!(expression.evaluate(&rules).value, Ok(19.into()));
+
+        assert_eq!(
+            "(34 - 4".parse::<Expression>(),
+            Err(ParseExpressionError::End)
+        );
+        assert_eq!(
+            "34 $ 4".parse::<Expression>(),
+            Err(ParseExpressionError::Unexpected {
+                symbol: '$',
+                position: 3
+            })
+        );
+        let error = "34\u{a0}$ 4".parse::<Expression>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid expression, unexpected `$` at character 4"
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use std::{cmp::Ordering, fmt::Display};
+
+/**
+    Exact fraction `num / den`
+
+    Always reduced, with a positive denominator.
+    Whole numbers have a denominator of 1, and use fast paths that skip the reduction.
+
+    Every operation is checked : `None` is returned instead of overflowing
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fraction {
+    num: i128,
+    den: i128,
+}
+impl Fraction {
+    pub const ZERO: Self = Self::integer(0);
+
+    /// Whole number `value`
+    pub const fn integer(value: i128) -> Self {
+        Self { num: value, den: 1 }
+    }
+
+    /// Reduced fraction `num / den`. `None` if `den` is zero or on overflow
+    pub fn new(num: i128, den: i128) -> Option<Self> {
+        if den == 0 {
+            return None;
+        }
+        let divisor = gcd(num, den)?.checked_mul(den.signum())?;
+        Some(Self {
+            num: num.checked_div(divisor)?,
+            den: den.checked_div(divisor)?,
+        })
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.den == 1
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.num < 0
+    }
+
+    /// The whole number value, `None` if this is not a whole number
+    pub fn to_integer(self) -> Option<i128> {
+        self.is_integer().then_some(self.num)
+    }
+
+    /// Largest whole number less than or equal to `self`
+    pub fn floor(self) -> Self {
+        Self::integer(self.num.div_euclid(self.den))
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        if self.is_integer() && rhs.is_integer() {
+            return self.num.checked_add(rhs.num).map(Self::integer);
+        }
+        // a/b + c/d = (a * d/g + c * b/g) / (b/g * d) with g = gcd(b, d)
+        let g = gcd(self.den, rhs.den)?;
+        let num =
+            (self.num.checked_mul(rhs.den / g)?).checked_add(rhs.num.checked_mul(self.den / g)?)?;
+        Self::new(num, (self.den / g).checked_mul(rhs.den)?)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(Self {
+            num: rhs.num.checked_neg()?,
+            den: rhs.den,
+        })
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        if self.is_integer() && rhs.is_integer() {
+            return self.num.checked_mul(rhs.num).map(Self::integer);
+        }
+        Self::new(
+            self.num.checked_mul(rhs.num)?,
+            self.den.checked_mul(rhs.den)?,
+        )
+    }
+
+    /// `self` to the power of `exponent`, `None` on overflow
+    pub fn checked_pow(self, exponent: u32) -> Option<Self> {
+        Some(Self {
+            num: self.num.checked_pow(exponent)?,
+            den: self.den.checked_pow(exponent)?,
+        })
+    }
+
+    /// `self / rhs`, `None` if `rhs` is zero or on overflow
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        Self::new(
+            self.num.checked_mul(rhs.den)?,
+            self.den.checked_mul(rhs.num)?,
+        )
+    }
+}
+/// Compares integer parts first, then the inverted remainders, so it never overflows
+impl Ord for Fraction {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (self.floor().num, other.floor().num);
+        if a != b {
+            return a.cmp(&b);
+        }
+        let a = self.num - a * self.den;
+        let b = other.num - b * other.den;
+        match (a, b) {
+            (0, 0) => Ordering::Equal,
+            (0, _) => Ordering::Less,
+            (_, 0) => Ordering::Greater,
+            // a/x < b/y  <=>  x/a > y/b
+            (a, b) => Self {
+                num: other.den,
+                den: b,
+            }
+            .cmp(&Self {
+                num: self.den,
+                den: a,
+            }),
+        }
+    }
+}
+impl PartialOrd for Fraction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl From<u32> for Fraction {
+    fn from(value: u32) -> Self {
+        Self::integer(value as i128)
+    }
+}
+impl Display for Fraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.is_integer() {
+            true => write!(f, "{}", self.num),
+            false => write!(f, "{}/{}", self.num, self.den),
+        }
+    }
+}
+
+/// Greatest common divisor, always positive unless both inputs are zero. `None` on overflow
+fn gcd(a: i128, b: i128) -> Option<i128> {
+    let (mut a, mut b) = (a.checked_abs()?, b.checked_abs()?);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    Some(a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let half = Fraction::new(1, 2).unwrap();
+        let seven_halves = Fraction::new(-14, -4).unwrap();
+        assert_eq!(seven_halves.to_string(), "7/2");
+        assert_eq!(
+            seven_halves.checked_mul(Fraction::integer(4)),
+            Some(Fraction::integer(14))
+        );
+        assert_eq!(seven_halves.checked_sub(half), Some(Fraction::integer(3)));
+        assert_eq!(seven_halves.floor(), Fraction::integer(3));
+        assert_eq!(Fraction::new(-7, 2).unwrap().floor(), Fraction::integer(-4));
+        assert_eq!(half.checked_div(Fraction::ZERO), None);
+        assert!(half < seven_halves && Fraction::ZERO < half);
+        assert!(Fraction::new(-1, 3).unwrap() < Fraction::new(-1, 4).unwrap());
+    }
+
+    #[test]
+    fn overflow() {
+        let max = Fraction::integer(i128::MAX);
+        assert_eq!(max.checked_add(Fraction::integer(1)), None);
+        assert_eq!(max.checked_mul(Fraction::integer(2)), None);
+        let huge = Fraction::new(i128::MAX, 3).unwrap();
+        assert_eq!(huge.checked_mul(huge), None);
+        assert!(huge < max, "comparison must not overflow");
+        assert!(Fraction::new(i128::MAX - 1, i128::MAX).unwrap() < Fraction::integer(1));
+    }
+}
@@ -0,0 +1,216 @@
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use crate::calculator::{Letter, encoding::LetterEncoding};
+
+/**
+    Alternative reading of cores that are not a letter under the [`LetterEncoding`]
+
+    Examples are given for the letters of the game, A = 1 to Z = 26
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum Reading {
+    /// Wraps around the alphabet : 27 is A, 0 is Z
+    #[display("modulo")]
+    Modulo,
+    /// Splits the digits into pairs : 1226 is L Z
+    #[display("pairs")]
+    Pairs,
+    /// Counts one number lower : 0 is A
+    #[display("zero")]
+    Zero,
+}
+impl Reading {
+    /// Every reading, in the order they are tried
+    pub const ALL: [Self; 3] = [Self::Modulo, Self::Pairs, Self::Zero];
+
+    /**
+        Reads the `core` as letters of the `encoding`
+
+        Returns `None` if the reading does not produce letters
+    */
+    pub fn read(self, core: u32, encoding: LetterEncoding) -> Option<Vec<Letter>> {
+        match self {
+            Self::Modulo => {
+                let numbers = encoding.numbers();
+                let count = numbers.end() - numbers.start() + 1;
+                let offset = (core % count + count - numbers.start() % count) % count;
+                encoding.letter(numbers.start() + offset).map(|l| vec![l])
+            }
+            Self::Pairs => {
+                let digits = core.to_string();
+                let digits = match digits.len() % 2 {
+                    0 => digits,
+                    _ => format!("0{digits}"),
+                };
+                digits
+                    .as_bytes()
+                    .chunks(2)
+                    .map(|pair| {
+                        let pair = std::str::from_utf8(pair).ok()?.parse().ok()?;
+                        encoding.letter(pair)
+                    })
+                    .collect()
+            }
+            Self::Zero => core
+                .checked_add(1)
+                .and_then(|core| encoding.letter(core))
+                .map(|l| vec![l]),
+        }
+    }
+}
+
+/// Set of the [`Reading`]s to try
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub struct Readings(u8);
+impl Readings {
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn all() -> Self {
+        Self(u8::MAX >> (8 - Reading::ALL.len()))
+    }
+
+    /// Same set, including `reading`
+    pub const fn with(self, reading: Reading) -> Self {
+        Self(self.0 | 1 << reading as u8)
+    }
+
+    pub fn contains(self, reading: Reading) -> bool {
+        self.0 & 1 << reading as u8 != 0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Readings of the set, in the order they are tried
+    pub fn iter(self) -> impl Iterator<Item = Reading> {
+        Reading::ALL
+            .into_iter()
+            .filter(move |reading| self.contains(*reading))
+    }
+
+    /// Next selection when cycling through : none, each reading alone, then all of them
+    pub fn cycle(self) -> Self {
+        if self.is_empty() {
+            return Reading::ALL[0].into();
+        }
+        match Reading::ALL
+            .iter()
+            .position(|&reading| self == reading.into())
+        {
+            Some(i) => Reading::ALL
+                .get(i + 1)
+                .map_or(Self::all(), |&next| next.into()),
+            None => Self::empty(),
+        }
+    }
+}
+impl From<Reading> for Readings {
+    fn from(reading: Reading) -> Self {
+        Self::empty().with(reading)
+    }
+}
+impl Debug for Readings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/**
+    Alternative readings of a `core` that is not a letter of the `encoding`
+
+    Returns every selected reading that produces letters, nothing if the core already is a letter
+*/
+pub fn interpret(
+    core: u32,
+    readings: Readings,
+    encoding: LetterEncoding,
+) -> Vec<(Reading, Vec<Letter>)> {
+    if encoding.letter(core).is_some() {
+        return Vec::new();
+    }
+    readings
+        .iter()
+        .filter_map(|reading| Some((reading, reading.read(core, encoding)?)))
+        .collect()
+}
+
+/// Writes the names of the readings in the set
+impl Display for Readings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "none");
+        }
+        write!(f, "{}", itertools::join(self.iter(), ","))
+    }
+}
+/// Parses a comma separated list of readings, `all` or `none`
+impl FromStr for Readings {
+    type Err = ParseReadingError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|name| match name.trim().to_ascii_lowercase().as_str() {
+                "modulo" => Ok(Reading::Modulo.into()),
+                "pairs" => Ok(Reading::Pairs.into()),
+                "zero" => Ok(Reading::Zero.into()),
+                "all" => Ok(Self::all()),
+                "none" => Ok(Self::empty()),
+                _ => Err(ParseReadingError),
+            })
+            .try_fold(Self::empty(), |set, readings| Ok(Self(set.0 | readings?.0)))
+    }
+}
+
+#[derive(Debug, derive_more::Display, derive_more::Error)]
+#[display("Invalid reading, expected a list of : modulo, pairs, zero, all, none")]
+pub struct ParseReadingError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(letters: Vec<Letter>) -> String {
+        letters.into_iter().collect()
+    }
+
+    #[test]
+    fn readings() {
+        let a1z26 = LetterEncoding::A1Z26;
+        assert_eq!(Reading::Modulo.read(27, a1z26).map(text), Some("A".into()));
+        assert_eq!(Reading::Modulo.read(0, a1z26).map(text), Some("Z".into()));
+        assert_eq!(
+            Reading::Pairs.read(1226, a1z26).map(text),
+            Some("LZ".into())
+        );
+        assert_eq!(Reading::Pairs.read(105, a1z26).map(text), Some("AE".into()));
+        assert_eq!(Reading::Pairs.read(1299, a1z26).map(text), None);
+        assert_eq!(Reading::Zero.read(0, a1z26).map(text), Some("A".into()));
+        assert_eq!(Reading::Zero.read(26, a1z26).map(text), None);
+
+        assert!(interpret(12, Readings::all(), a1z26).is_empty());
+        let readings = interpret(1226, Readings::all(), a1z26);
+        assert_eq!(readings.len(), 2);
+        assert_eq!(
+            "pairs,modulo".parse::<Readings>().unwrap(),
+            Readings::from(Reading::Modulo).with(Reading::Pairs)
+        );
+        assert_eq!(Readings::all().to_string(), "modulo,pairs,zero");
+        assert_eq!(Readings::from(Reading::Zero).cycle(), Readings::all());
+        assert_eq!(Readings::all().cycle(), Readings::empty());
+
+        let a0z25 = LetterEncoding::A0Z25;
+        assert!(interpret(0, Readings::all(), a0z25).is_empty());
+        assert_eq!(Reading::Modulo.read(26, a0z25).map(text), Some("A".into()));
+        assert_eq!(
+            Reading::Pairs.read(1125, a0z25).map(text),
+            Some("LZ".into())
+        );
+        let keypad = LetterEncoding::Keypad;
+        assert_eq!(Reading::Modulo.read(10, keypad).map(text), Some("A".into()));
+    }
+}
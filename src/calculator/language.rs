@@ -0,0 +1,162 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::calculator::{CORE_LENGTHS, rules::ParseRuleError, text::tokenize};
+
+/// Letter of a language beyond `A..=Z`, and how it is folded into the letters of the cypher
+struct Folding {
+    upper: char,
+    lower: char,
+    /// Folded letters, used when the word keeps a supported length
+    long: &'static str,
+    /// Single folded letter, used otherwise
+    short: char,
+}
+
+const fn folding(upper: char, lower: char, long: &'static str, short: char) -> Folding {
+    Folding {
+        upper,
+        lower,
+        long,
+        short,
+    }
+}
+
+const FRENCH: &[Folding] = &[
+    folding('À', 'à', "A", 'A'),
+    folding('Â', 'â', "A", 'A'),
+    folding('Æ', 'æ', "AE", 'A'),
+    folding('Ç', 'ç', "C", 'C'),
+    folding('É', 'é', "E", 'E'),
+    folding('È', 'è', "E", 'E'),
+    folding('Ê', 'ê', "E", 'E'),
+    folding('Ë', 'ë', "E", 'E'),
+    folding('Î', 'î', "I", 'I'),
+    folding('Ï', 'ï', "I", 'I'),
+    folding('Ô', 'ô', "O", 'O'),
+    folding('Œ', 'œ', "OE", 'O'),
+    folding('Ù', 'ù', "U", 'U'),
+    folding('Û', 'û', "U", 'U'),
+    folding('Ü', 'ü', "U", 'U'),
+    folding('Ÿ', 'ÿ', "Y", 'Y'),
+];
+
+const GERMAN: &[Folding] = &[
+    folding('Ä', 'ä', "A", 'A'),
+    folding('Ö', 'ö', "O", 'O'),
+    folding('Ü', 'ü', "U", 'U'),
+    folding('ẞ', 'ß', "SS", 'S'),
+];
+
+/**
+    Language of the words to decode, defining the letters accepted beyond `A..=Z`
+
+    Accented letters are folded into the letters of the cypher : `É` is `E`.
+    Ligatures like `ß` are expanded to `SS` when the word keeps a supported length, otherwise folded to `S`
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+    German,
+}
+impl Language {
+    pub const ALL: [Self; 3] = [Self::English, Self::French, Self::German];
+
+    /// Letters of the alphabet beyond `A..=Z`
+    fn extra_letters(self) -> &'static [Folding] {
+        match self {
+            Self::English => &[],
+            Self::French => FRENCH,
+            Self::German => GERMAN,
+        }
+    }
+
+    /// Folds the letters of every word of the `text`, leaving everything else untouched
+    pub fn fold(self, text: &str) -> String {
+        if text.is_ascii() {
+            return text.to_string();
+        }
+        let mut output = String::with_capacity(text.len());
+        let mut copied = 0;
+        for (_, span) in tokenize(text) {
+            output.push_str(&text[copied..span.start]);
+            output.push_str(&self.fold_word(&text[span.clone()]));
+            copied = span.end;
+        }
+        output.push_str(&text[copied..]);
+        output
+    }
+
+    /// Folds the letters of a `word`, expanding ligatures only if the word keeps a supported length
+    fn fold_word(self, word: &str) -> String {
+        let fold = |long: bool| {
+            let mut output = String::with_capacity(word.len());
+            for c in word.chars() {
+                let folding = self
+                    .extra_letters()
+                    .iter()
+                    .find(|folding| folding.upper == c || folding.lower == c);
+                match (folding, long) {
+                    (None, _) => output.push(c),
+                    (Some(folding), true) if folding.lower == c => {
+                        output.push_str(&folding.long.to_ascii_lowercase())
+                    }
+                    (Some(folding), true) => output.push_str(folding.long),
+                    (Some(folding), false) if folding.lower == c => {
+                        output.push(folding.short.to_ascii_lowercase())
+                    }
+                    (Some(folding), false) => output.push(folding.short),
+                }
+            }
+            output
+        };
+        let long = fold(true);
+        let short = fold(false);
+        match CORE_LENGTHS.contains(&long.chars().count())
+            || !CORE_LENGTHS.contains(&short.chars().count())
+        {
+            true => long,
+            false => short,
+        }
+    }
+}
+impl Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::English => write!(f, "english"),
+            Self::French => write!(f, "french"),
+            Self::German => write!(f, "german"),
+        }
+    }
+}
+impl FromStr for Language {
+    type Err = ParseRuleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|language| language.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or(ParseRuleError {
+                expected: "english, french, german",
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diacritics() {
+        assert_eq!(Language::French.fold("ÉTÉ, Noël !"), "ETE, Noel !");
+        assert_eq!(Language::French.fold("cœur"), "coeur");
+        assert_eq!(Language::German.fold("ÜBER Maß"), "UBER Mass");
+        assert_eq!(
+            Language::German.fold("Straße"),
+            "Strase",
+            "STRASSE is too long for a core"
+        );
+        assert_eq!(Language::German.fold("ÉTÉ"), "ÉTÉ");
+        assert_eq!(Language::English.fold("ÜBER"), "ÜBER");
+    }
+}
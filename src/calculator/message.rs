@@ -0,0 +1,209 @@
+use std::collections::{BTreeMap, BTreeSet, btree_map::Entry};
+
+use crate::calculator::{
+    Letter,
+    dictionary::Dictionary,
+    encryptor::{EncryptError, encrypt_letter},
+    pattern::Pattern,
+    rules::CoreRules,
+    search::SearchLimits,
+};
+
+/// Candidate cypher words of every letter in a plaintext message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageCandidates {
+    /// Letters of each word of the message
+    pub words: Vec<Vec<LetterCandidates>>,
+}
+
+/// Every cypher word reaching a letter of the message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetterCandidates {
+    pub letter: Letter,
+    pub words: Vec<Vec<Letter>>,
+}
+
+/// Constraints used to pick a single cyphertext
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PickOptions<'a> {
+    /// Never use the same cypher word twice
+    pub unique: bool,
+    /// Dictionary words are picked before any other candidate, when available
+    pub preferred: Option<&'a Dictionary>,
+    /// Same seed, same cyphertext
+    pub seed: u64,
+}
+
+/// Cypher words of each word of the message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cyphertext {
+    pub words: Vec<Vec<Vec<Letter>>>,
+}
+
+/**
+    Computes the candidate `length`-letter cypher words of every letter in the `message`
+
+    Words of the message are separated by whitespace, letters may be uppercase or lowercase
+*/
+pub fn encrypt_message(
+    message: &str,
+    length: usize,
+    rules: &CoreRules,
+) -> Result<MessageCandidates, EncryptError> {
+    let letters = message
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .map(|c| Letter::try_from_char(c).ok_or(EncryptError::InputLetter(c)))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if letters.is_empty() {
+        return Err(EncryptError::InputEmpty);
+    }
+
+    let mut cache = BTreeMap::<Letter, Vec<Vec<Letter>>>::new();
+    let words = letters
+        .into_iter()
+        .map(|word| {
+            word.into_iter()
+                .map(|letter| {
+                    let words = match cache.entry(letter) {
+                        Entry::Occupied(entry) => entry.get().clone(),
+                        Entry::Vacant(entry) => {
+                            let pattern = Pattern::any(length);
+                            let search =
+                                encrypt_letter(letter, &pattern, rules, &SearchLimits::UNLIMITED)?;
+                            entry.insert(search.words).clone()
+                        }
+                    };
+                    match words.is_empty() {
+                        true => Err(EncryptError::NoCandidate(letter)),
+                        false => Ok(LetterCandidates { letter, words }),
+                    }
+                })
+                .collect()
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(MessageCandidates { words })
+}
+
+impl MessageCandidates {
+    /**
+        Picks one cypher word for every letter, following the `options`
+
+        Dictionary words are picked first, then any other candidate
+    */
+    pub fn pick(&self, options: &PickOptions) -> Result<Cyphertext, EncryptError> {
+        let mut rng = SplitMix64(options.seed);
+        let mut used = BTreeSet::new();
+        let mut pick = |candidates: &LetterCandidates| {
+            let available = |word: &&Vec<Letter>| !options.unique || !used.contains(*word);
+            let preferred = candidates
+                .words
+                .iter()
+                .filter(available)
+                .filter(|word| options.preferred.is_some_and(|set| set.contains(word)))
+                .collect::<Vec<_>>();
+            let pool = match preferred.is_empty() {
+                false => preferred,
+                true => candidates.words.iter().filter(available).collect(),
+            };
+            if pool.is_empty() {
+                return Err(EncryptError::NotEnoughCandidates(candidates.letter));
+            }
+            let word = pool[rng.below(pool.len())].clone();
+            used.insert(word.clone());
+            Ok(word)
+        };
+
+        let words = self
+            .words
+            .iter()
+            .map(|word| word.iter().map(&mut pick).collect())
+            .collect::<Result<_, _>>()?;
+        Ok(Cyphertext { words })
+    }
+}
+
+/// Words separated by spaces, one message word per line
+impl std::fmt::Display for Cyphertext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self.words.iter().map(|word| {
+            itertools::join(
+                word.iter()
+                    .map(|cypher| cypher.iter().copied().collect::<String>()),
+                " ",
+            )
+        });
+        write!(f, "{}", itertools::join(lines, "\n"))
+    }
+}
+
+/// Small deterministic random number generator, so a seed always gives the same cyphertext
+struct SplitMix64(u64);
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Number in `0..bound`
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::calculator::decryptor::decrypt_numbers;
+
+    use super::*;
+
+    #[test]
+    fn messages() {
+        let rules = CoreRules::default();
+        let candidates = encrypt_message("hello World", 3, &rules).unwrap();
+        assert_eq!(candidates.words.len(), 2);
+        assert_eq!(candidates.words[0].len(), 5);
+
+        let options = PickOptions {
+            unique: true,
+            seed: 7,
+            ..Default::default()
+        };
+        let cyphertext = candidates.pick(&options).unwrap();
+        assert_eq!(cyphertext, candidates.pick(&options).unwrap());
+        let letters = candidates.words.iter().flatten().map(|c| c.letter);
+        let cyphers = cyphertext.words.iter().flatten().collect::<Vec<_>>();
+        for (letter, cypher) in letters.zip(&cyphers) {
+            let numbers = cypher.iter().map(|l| rules.encoding.number(*l));
+            let numbers = numbers.collect::<Vec<_>>();
+            assert_eq!(
+                decrypt_numbers(&numbers, &rules),
+                Ok(rules.encoding.number(letter))
+            );
+        }
+        assert!(cyphers.iter().all_unique());
+        assert_eq!(cyphertext.to_string().lines().count(), 2);
+
+        let word = candidates.words[0][0].words[1].clone();
+        let preferred = Dictionary::parse(&word.iter().copied().collect::<String>());
+        let options = PickOptions {
+            preferred: Some(&preferred),
+            ..options
+        };
+        let cyphertext = candidates.pick(&options).unwrap();
+        assert_eq!(cyphertext.words[0][0], word);
+
+        assert_eq!(
+            encrypt_message("A1", 4, &rules),
+            Err(EncryptError::InputLetter('1'))
+        );
+    }
+}
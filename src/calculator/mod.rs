@@ -1,61 +1,55 @@
 pub mod decryptor;
+pub mod dictionary;
+pub mod encoding;
 pub mod encryptor;
-
-/// Core is composed of 4 numbers
+pub mod expression;
+pub mod fraction;
+pub mod interpret;
+pub mod language;
+pub mod message;
+pub mod operator;
+pub mod pattern;
+pub mod rules;
+pub mod search;
+pub mod stats;
+pub mod table;
+pub mod text;
+pub mod verify;
+
+/// Core is composed of 4 numbers by default
 pub const CORE_LENGTH: usize = 4;
 
-use std::{fmt::Display, ops::RangeInclusive, str::FromStr};
-
-use bitflags::bitflags;
-
-bitflags! {
-    /**
-        Remaining available operations
+/// Supported amount of numbers in a core
+pub const CORE_LENGTHS: RangeInclusive<usize> = 3..=6;
 
-        The first operation is always an addition,
-        the 3 remaining operation (substraction, multiply and division) are implemented as a bitflag.
+use std::{fmt::Display, ops::RangeInclusive, str::FromStr};
 
-        Each flag represents a possible operation that remains to be tested
-     */
-    #[derive(Debug, Clone, Copy, PartialEq)]
-    struct Operation: u8 {
-        const SUB = 1;
-        const MUL = 1 << 1;
-        const DIV = 1 << 2;
-    }
-}
-impl Operation {
-    /**
-        Applies the operation `a OP b`
-
-        `self` if must contain only one operation.
-        The caller is expected to loop over the available operations using `Op::iter()` and only call `apply()` on each individual operation
-
-        Returns `Some(value)` if the operation is mathematically valid :
-
-        | Op    | Validity     |
-        | ----- | :--------    |
-        | `MUL` | Always valid |
-        | `DIV` | Result must be a whole number |
-        | `SUB` | Result must be a positive number |
-
-    */
-    fn apply(&self, a: u32, b: u32) -> Option<u32> {
-        match *self {
-            Self::SUB => a.checked_sub(b),
-            Self::MUL => Some(a * b),
-            Self::DIV => {
-                if a.checked_rem(b) != Some(0) {
-                    return None;
-                }
-                a.checked_div(b)
-            }
-            _ => unreachable!("Unknown operation: bitflag {self:?}"),
-        }
-    }
+/// Reason why an operation could not be applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum DeadBranch {
+    #[display("negative subtraction")]
+    NegativeSubtraction,
+    #[display("negative value")]
+    NegativeValue,
+    #[display("operand is not a whole number")]
+    NonIntegerOperand,
+    #[display("negative operand")]
+    NegativeOperand,
+    #[display("non-integer division")]
+    NonIntegerDivision,
+    #[display("division by zero")]
+    DivisionByZero,
+    #[display("division with a remainder, rejected by the rules")]
+    RejectedDivision,
+    #[display("overflow")]
+    Overflow,
+    #[display("core out of range")]
+    CoreOutOfRange,
+    #[display("core is not a whole number")]
+    FractionalCore,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Letter(char);
 impl Letter {
     pub fn try_from_char(c: char) -> Option<Self> {
@@ -66,6 +60,7 @@ impl Letter {
         }
     }
 
+    /// Letter at a position in the alphabet, `A` being 1. Cypher numbers depend on the [`LetterEncoding`](encoding::LetterEncoding)
     pub fn try_from_num(num: u32) -> Option<Self> {
         ALPHABET
             .contains(&num)
@@ -73,6 +68,7 @@ impl Letter {
             .map(Self)
     }
 
+    /// Position in the alphabet, `A` being 1. Cypher numbers depend on the [`LetterEncoding`](encoding::LetterEncoding)
     pub fn to_num(self) -> u32 {
         1 + self.0 as u32 - 'A' as u32
     }
@@ -129,10 +125,12 @@ pub struct ParseLetterError;
 const ALPHABET: RangeInclusive<u32> = 1..=26;
 
 #[cfg(test)]
+#[allow(clippy::redundant_closure)]
 mod tests {
     use itertools::Itertools;
 
     use super::*;
+    use crate::calculator::encoding::LetterEncoding;
 
     #[test]
     fn alphabet() {
@@ -164,5 +162,16 @@ mod tests {
         assert_eq!(numbers, strings);
         assert_eq!(chars_uppercase, chars_lowercase);
         assert_eq!(numbers, chars_lowercase);
+
+        for encoding in LetterEncoding::ALL {
+            for letter in numbers {
+                let number = encoding.number(letter);
+                let decoded = encoding.letter(number).unwrap();
+                assert_eq!(encoding.number(decoded), number, "{encoding} {letter}");
+                if encoding != LetterEncoding::Keypad {
+                    assert_eq!(decoded, letter, "{encoding}");
+                }
+            }
+        }
     }
 }
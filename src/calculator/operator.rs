@@ -0,0 +1,421 @@
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use crate::calculator::{
+    DeadBranch,
+    fraction::Fraction,
+    rules::{Arithmetic, CoreRules, DivisionRule, ParseRuleError},
+};
+
+/**
+    An operation combining numbers during the decryption
+
+    Operators are listed in [`OPERATORS`] and referred to by an [`Operation`].
+    Adding an operator to the list makes it available to the rules, the decryption and the expressions.
+
+    Operators have no arity : an operation order combines the result so far with the next number,
+    so an operator taking one or three operands could not be placed in it.
+    Every operator is binary by the signature of [`Operator::compute`], instead of an arity checked when it is applied
+*/
+pub trait Operator: Sync {
+    /// Symbol of the operator in rules, operation orders and expressions
+    fn symbol(&self) -> char;
+
+    /// Binding strength in expressions, operators with a higher precedence are applied first
+    fn precedence(&self) -> u8;
+
+    /// Result of `a` combined with `b`, or the reason why it is undefined
+    fn compute(&self, a: Fraction, b: Fraction, rules: &CoreRules) -> Result<Fraction, DeadBranch>;
+
+    /// Checks a result against the `rules` : values below zero are only valid if negatives are allowed
+    fn validate(&self, value: Fraction, rules: &CoreRules) -> Result<(), DeadBranch> {
+        match value.is_negative() && !rules.allow_negative {
+            true => Err(DeadBranch::NegativeValue),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Amount of operators in [`OPERATORS`]
+const OPERATOR_COUNT: usize = 8;
+
+/// Every operator, in the order they are tried by the decryption
+pub static OPERATORS: [&dyn Operator; OPERATOR_COUNT] = [
+    &Subtract,
+    &Multiply,
+    &Divide,
+    &Add,
+    &Modulo,
+    &Power,
+    &Concatenate,
+    &AbsoluteDifference,
+];
+
+/// `a + b`, always valid
+struct Add;
+impl Operator for Add {
+    fn symbol(&self) -> char {
+        '+'
+    }
+    fn precedence(&self) -> u8 {
+        1
+    }
+    fn compute(&self, a: Fraction, b: Fraction, _: &CoreRules) -> Result<Fraction, DeadBranch> {
+        a.checked_add(b).ok_or(DeadBranch::Overflow)
+    }
+}
+
+/// `a - b`, the result must be a positive number, unless negatives are allowed
+struct Subtract;
+impl Operator for Subtract {
+    fn symbol(&self) -> char {
+        '-'
+    }
+    fn precedence(&self) -> u8 {
+        1
+    }
+    fn compute(&self, a: Fraction, b: Fraction, _: &CoreRules) -> Result<Fraction, DeadBranch> {
+        a.checked_sub(b).ok_or(DeadBranch::Overflow)
+    }
+    fn validate(&self, value: Fraction, rules: &CoreRules) -> Result<(), DeadBranch> {
+        match value.is_negative() && !rules.allow_negative {
+            true => Err(DeadBranch::NegativeSubtraction),
+            false => Ok(()),
+        }
+    }
+}
+
+/// `a * b`, always valid
+struct Multiply;
+impl Operator for Multiply {
+    fn symbol(&self) -> char {
+        '*'
+    }
+    fn precedence(&self) -> u8 {
+        2
+    }
+    fn compute(&self, a: Fraction, b: Fraction, _: &CoreRules) -> Result<Fraction, DeadBranch> {
+        a.checked_mul(b).ok_or(DeadBranch::Overflow)
+    }
+}
+
+/// `a / b`, the result must be a whole number, unless [`DivisionRule::Floor`] or [`Arithmetic::Rational`]
+struct Divide;
+impl Operator for Divide {
+    fn symbol(&self) -> char {
+        '/'
+    }
+    fn precedence(&self) -> u8 {
+        2
+    }
+    fn compute(&self, a: Fraction, b: Fraction, rules: &CoreRules) -> Result<Fraction, DeadBranch> {
+        if b == Fraction::ZERO {
+            return Err(DeadBranch::DivisionByZero);
+        }
+        let quotient = a.checked_div(b).ok_or(DeadBranch::Overflow)?;
+        match (quotient.is_integer(), rules.arithmetic, rules.division) {
+            (true, _, _) | (false, Arithmetic::Rational, _) => Ok(quotient),
+            (false, Arithmetic::Integer, DivisionRule::Floor) => Ok(quotient.floor()),
+            (false, Arithmetic::Integer, DivisionRule::Exact) => {
+                Err(DeadBranch::NonIntegerDivision)
+            }
+            (false, Arithmetic::Integer, DivisionRule::Reject) => Err(DeadBranch::RejectedDivision),
+        }
+    }
+}
+
+/// Remainder of `a / b`, between 0 and `b`. Both numbers must be whole numbers
+struct Modulo;
+impl Operator for Modulo {
+    fn symbol(&self) -> char {
+        '%'
+    }
+    fn precedence(&self) -> u8 {
+        2
+    }
+    fn compute(&self, a: Fraction, b: Fraction, _: &CoreRules) -> Result<Fraction, DeadBranch> {
+        let [a, b] = integers(a, b)?;
+        if b == 0 {
+            return Err(DeadBranch::DivisionByZero);
+        }
+        a.checked_rem_euclid(b)
+            .map(Fraction::integer)
+            .ok_or(DeadBranch::Overflow)
+    }
+}
+
+/// `a` to the power of `b`, the exponent must be a whole positive number
+struct Power;
+impl Operator for Power {
+    fn symbol(&self) -> char {
+        '^'
+    }
+    fn precedence(&self) -> u8 {
+        3
+    }
+    fn compute(&self, a: Fraction, b: Fraction, _: &CoreRules) -> Result<Fraction, DeadBranch> {
+        let exponent = b.to_integer().ok_or(DeadBranch::NonIntegerOperand)?;
+        if exponent < 0 {
+            return Err(DeadBranch::NegativeOperand);
+        }
+        u32::try_from(exponent)
+            .ok()
+            .and_then(|exponent| a.checked_pow(exponent))
+            .ok_or(DeadBranch::Overflow)
+    }
+}
+
+/// Digits of `a` followed by the digits of `b` : `34 & 4` is 344. Both numbers must be whole positive numbers
+struct Concatenate;
+impl Operator for Concatenate {
+    fn symbol(&self) -> char {
+        '&'
+    }
+    fn precedence(&self) -> u8 {
+        3
+    }
+    fn compute(&self, a: Fraction, b: Fraction, _: &CoreRules) -> Result<Fraction, DeadBranch> {
+        let [a, b] = integers(a, b)?;
+        if a < 0 || b < 0 {
+            return Err(DeadBranch::NegativeOperand);
+        }
+        let digits = b.checked_ilog10().unwrap_or_default() + 1;
+        10i128
+            .checked_pow(digits)
+            .and_then(|shift| a.checked_mul(shift))
+            .and_then(|a| a.checked_add(b))
+            .map(Fraction::integer)
+            .ok_or(DeadBranch::Overflow)
+    }
+}
+
+/// `|a - b|`, always valid
+struct AbsoluteDifference;
+impl Operator for AbsoluteDifference {
+    fn symbol(&self) -> char {
+        '~'
+    }
+    fn precedence(&self) -> u8 {
+        1
+    }
+    fn compute(&self, a: Fraction, b: Fraction, _: &CoreRules) -> Result<Fraction, DeadBranch> {
+        let (low, high) = if a < b { (a, b) } else { (b, a) };
+        high.checked_sub(low).ok_or(DeadBranch::Overflow)
+    }
+}
+
+/// Operands of an operator defined on whole numbers
+fn integers(a: Fraction, b: Fraction) -> Result<[i128; 2], DeadBranch> {
+    match (a.to_integer(), b.to_integer()) {
+        (Some(a), Some(b)) => Ok([a, b]),
+        _ => Err(DeadBranch::NonIntegerOperand),
+    }
+}
+
+/// An operator of [`OPERATORS`], referred to by its position in the list
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Operation(u8);
+impl Operation {
+    pub const SUB: Self = Self(0);
+    pub const MUL: Self = Self(1);
+    pub const DIV: Self = Self(2);
+
+    /// Every operation, in the order they are tried by the decryption
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..OPERATOR_COUNT as u8).map(Self)
+    }
+
+    /// Operation written with the `symbol`
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        Self::all().find(|op| op.symbol() == symbol)
+    }
+
+    fn operator(self) -> &'static dyn Operator {
+        OPERATORS[self.0 as usize]
+    }
+
+    /// Mathematical symbol of the operation
+    pub fn symbol(self) -> char {
+        self.operator().symbol()
+    }
+
+    /// Binding strength in expressions, see [`Operator::precedence`]
+    pub fn precedence(self) -> u8 {
+        self.operator().precedence()
+    }
+
+    /**
+        Applies the operation `a OP b`
+
+        Computations are checked and never overflow.
+        Returns `Ok(value)` if the operation is mathematically valid under the given `rules`,
+        otherwise returns the reason why this branch of the computation is dead
+    */
+    pub(super) fn apply(
+        self,
+        a: Fraction,
+        b: Fraction,
+        rules: &CoreRules,
+    ) -> Result<Fraction, DeadBranch> {
+        let operator = self.operator();
+        let value = operator.compute(a, b, rules)?;
+        operator.validate(value, rules)?;
+        Ok(value)
+    }
+}
+impl Debug for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Operation({})", self.symbol())
+    }
+}
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// Set of operations, like the operations available in the [`CoreRules`]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct OperationSet(u16);
+impl OperationSet {
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Same set, including `op`
+    pub const fn with(self, op: Operation) -> Self {
+        Self(self.0 | 1 << op.0)
+    }
+
+    pub fn contains(self, op: Operation) -> bool {
+        self.0 & 1 << op.0 != 0
+    }
+
+    /// Operations of the set, in the order they are tried by the decryption
+    pub fn iter(self) -> impl Iterator<Item = Operation> {
+        Operation::all().filter(move |op| self.contains(*op))
+    }
+}
+impl From<Operation> for OperationSet {
+    fn from(op: Operation) -> Self {
+        Self::empty().with(op)
+    }
+}
+impl Debug for OperationSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+/// Writes the symbol of every operation in the set
+impl Display for OperationSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.iter().try_for_each(|op| write!(f, "{op}"))
+    }
+}
+/// Parses a set of operations from their symbols, like `-*/`
+impl FromStr for OperationSet {
+    type Err = ParseRuleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| {
+                Operation::from_symbol(c).ok_or(ParseRuleError {
+                    expected: "operation symbols + - * / % ^ & ~",
+                })
+            })
+            .try_fold(Self::empty(), |set, op| Ok(set.with(op?)))
+    }
+}
+
+/// Multiset of the operations that remain available for a computation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationPool {
+    /// Remaining uses of each operation, indexed like [`OPERATORS`]
+    counts: [u8; OPERATOR_COUNT],
+}
+impl OperationPool {
+    /**
+        Makes every operation of the `set` available enough times to combine `len` numbers
+
+        With the 3 operations of the game and 4 numbers, each operation is used once.
+        Longer cores allow each operation to be used more than once, shorter ones leave some operations unused
+    */
+    pub fn new(set: OperationSet, len: usize) -> Self {
+        let uses = len.saturating_sub(1).div_ceil(set.iter().count().max(1));
+        let mut counts = [0; OPERATOR_COUNT];
+        for op in set.iter() {
+            counts[op.0 as usize] = uses as u8;
+        }
+        Self { counts }
+    }
+
+    /// Every distinct operation that can still be used
+    pub fn iter(self) -> impl Iterator<Item = Operation> {
+        Operation::all().filter(move |op| self.counts[op.0 as usize] > 0)
+    }
+
+    /// Whether `op` can still be used
+    pub fn contains(self, op: Operation) -> bool {
+        self.counts[op.0 as usize] > 0
+    }
+
+    /// Same pool, with one less use of `op`
+    pub fn remove(mut self, op: Operation) -> Self {
+        self.counts[op.0 as usize] -= 1;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::CORE_LENGTH;
+
+    #[test]
+    fn operation_pool() {
+        let set = CoreRules::STANDARD.operations;
+        let pool = OperationPool::new(set, CORE_LENGTH);
+        assert_eq!(
+            pool.iter().collect::<Vec<_>>(),
+            set.iter().collect::<Vec<_>>()
+        );
+        let pool = pool.remove(Operation::SUB);
+        assert_eq!(
+            pool.iter().collect::<Vec<_>>(),
+            [Operation::MUL, Operation::DIV]
+        );
+        assert!(!pool.contains(Operation::SUB));
+
+        let pool = OperationPool::new(set, 6).remove(Operation::MUL);
+        assert_eq!(pool.iter().count(), 3);
+        assert_eq!(pool.remove(Operation::MUL).iter().count(), 2);
+    }
+
+    #[test]
+    fn operators() {
+        let rules = CoreRules::default();
+        let op = |symbol| Operation::from_symbol(symbol).unwrap();
+        let apply = |symbol, a: u32, b: u32| op(symbol).apply(a.into(), b.into(), &rules);
+        assert_eq!(apply('%', 34, 4), Ok(2.into()));
+        assert_eq!(apply('%', 34, 0), Err(DeadBranch::DivisionByZero));
+        assert_eq!(apply('^', 3, 4), Ok(81.into()));
+        assert_eq!(apply('^', 10, 40), Err(DeadBranch::Overflow));
+        assert_eq!(apply('&', 34, 4), Ok(344.into()));
+        assert_eq!(apply('&', 34, 0), Ok(340.into()));
+        assert_eq!(apply('~', 4, 34), Ok(30.into()));
+        assert_eq!(apply('-', 4, 34), Err(DeadBranch::NegativeSubtraction));
+
+        let half = Fraction::new(1, 2).unwrap();
+        assert_eq!(
+            op('%').apply(half, 2.into(), &rules),
+            Err(DeadBranch::NonIntegerOperand)
+        );
+
+        let set = "-*/%^&~".parse::<OperationSet>().unwrap();
+        assert_eq!(set.to_string(), "-*/%^&~");
+        assert!(!set.contains(op('+')));
+        assert!("-?".parse::<OperationSet>().is_err());
+    }
+}
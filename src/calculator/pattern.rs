@@ -0,0 +1,209 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::calculator::{ALPHABET, CORE_LENGTHS, Letter};
+
+/// Letters allowed in a slot of a [`Pattern`], bit 0 being `A`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LetterSet(u32);
+impl LetterSet {
+    /// Every letter
+    pub const ANY: Self = Self((1 << 26) - 1);
+
+    pub fn single(letter: Letter) -> Self {
+        Self(1 << (letter.to_num() - 1))
+    }
+
+    pub fn contains(self, letter: Letter) -> bool {
+        self.0 & Self::single(letter).0 != 0
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Allowed letters converted to cypher numbers, in alphabetical order
+    pub fn numbers(self) -> impl Iterator<Item = u32> {
+        ALPHABET.filter(move |number| self.0 & (1 << (number - 1)) != 0)
+    }
+
+    /// Cypher number of the `index`-th allowed letter
+    fn nth(self, index: usize) -> u32 {
+        match self == Self::ANY {
+            true => ALPHABET.start() + index as u32,
+            false => self.numbers().nth(index).unwrap_or_default(),
+        }
+    }
+}
+
+/**
+    Word pattern with a set of allowed letters for each slot
+
+    Written with a letter for a fixed slot, `?` for any letter and `[AEIOU]` for a set of letters : `T??E`, `?[AEIOU]??`
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    slots: Vec<LetterSet>,
+}
+impl Pattern {
+    /// Pattern of `length` wildcards
+    pub fn any(length: usize) -> Self {
+        Self {
+            slots: vec![LetterSet::ANY; length],
+        }
+    }
+
+    /// Length of the words matching the pattern
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn slots(&self) -> &[LetterSet] {
+        &self.slots
+    }
+
+    /// Whether every slot accepts any letter
+    pub fn is_any(&self) -> bool {
+        self.slots.iter().all(|slot| *slot == LetterSet::ANY)
+    }
+
+    pub fn matches(&self, word: &[Letter]) -> bool {
+        word.len() == self.len()
+            && self
+                .slots
+                .iter()
+                .zip(word)
+                .all(|(slot, letter)| slot.contains(*letter))
+    }
+
+    /// Number of words matching the pattern
+    pub fn combinations(&self) -> u64 {
+        self.slots.iter().map(|slot| slot.len() as u64).product()
+    }
+
+    /// Writes the cypher numbers of the word at `index`, among the matching words in alphabetical order
+    pub fn write_numbers(&self, mut index: u64, numbers: &mut [u32]) {
+        for (number, slot) in numbers.iter_mut().zip(&self.slots).rev() {
+            let base = slot.len() as u64;
+            *number = slot.nth((index % base) as usize);
+            index /= base;
+        }
+    }
+}
+impl Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for slot in &self.slots {
+            let letters = slot.numbers().filter_map(Letter::try_from_num);
+            match slot.len() {
+                1 => write!(f, "{}", itertools::join(letters, ""))?,
+                26 => write!(f, "?")?,
+                _ => write!(f, "[{}]", itertools::join(letters, ""))?,
+            }
+        }
+        Ok(())
+    }
+}
+/// Parses a pattern like `T??E` or `?[AEIOU]??`, case insensitive
+impl FromStr for Pattern {
+    type Err = ParsePatternError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut slots = Vec::new();
+        let mut chars = s.trim().chars().enumerate();
+        let letter = |(position, symbol): (usize, char)| {
+            Letter::try_from_char(symbol).ok_or(ParsePatternError::Unexpected { symbol, position })
+        };
+        while let Some((position, c)) = chars.next() {
+            let slot = match c {
+                '?' => LetterSet::ANY,
+                '[' => {
+                    let mut set = LetterSet(0);
+                    loop {
+                        match chars.next() {
+                            None => return Err(ParsePatternError::Unclosed { position }),
+                            Some((_, ']')) if set.len() > 0 => break,
+                            Some(c) => set.0 |= LetterSet::single(letter(c)?).0,
+                        }
+                    }
+                    set
+                }
+                c => LetterSet::single(letter((position, c))?),
+            };
+            slots.push(slot);
+        }
+        if !CORE_LENGTHS.contains(&slots.len()) {
+            return Err(ParsePatternError::Length);
+        }
+        Ok(Self { slots })
+    }
+}
+
+/// Malformed [`Pattern`], positions being counted in characters from 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum ParsePatternError {
+    #[display("Invalid pattern, expected 3 to 6 letters, '?' or letter sets like [AEIOU]")]
+    Length,
+    #[display("Invalid pattern, unexpected `{symbol}` at character {}", position + 1)]
+    Unexpected { symbol: char, position: usize },
+    #[display("Invalid pattern, `[` at character {} is never closed", position + 1)]
+    Unclosed { position: usize },
+}
+impl ParsePatternError {
+    /// Position of the faulty character, `None` if the whole pattern is at fault
+    pub fn position(self) -> Option<usize> {
+        match self {
+            Self::Length => None,
+            Self::Unexpected { position, .. } | Self::Unclosed { position } => Some(position),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns() {
+        let word = |s: &str| {
+            s.chars()
+                .filter_map(Letter::try_from_char)
+                .collect::<Vec<_>>()
+        };
+        let pattern: Pattern = "t?[aeiou]E".parse().unwrap();
+        assert_eq!(pattern.to_string(), "T?[AEIOU]E");
+        assert_eq!(pattern.combinations(), 26 * 5);
+        assert!(pattern.matches(&word("TREE")));
+        assert!(!pattern.matches(&word("TRYE")));
+        let mut numbers = [0; 4];
+        pattern.write_numbers(0, &mut numbers);
+        assert_eq!(numbers, [20, 1, 1, 5]);
+        pattern.write_numbers(6, &mut numbers);
+        assert_eq!(numbers, [20, 2, 5, 5]);
+
+        assert!(Pattern::any(4).is_any());
+        assert!("T?".parse::<Pattern>().is_err());
+        assert_eq!(
+            "T[]EE".parse::<Pattern>(),
+            Err(ParsePatternError::Unexpected {
+                symbol: ']',
+                position: 2
+            })
+        );
+        assert_eq!(
+            "T1EE".parse::<Pattern>(),
+            Err(ParsePatternError::Unexpected {
+                symbol: '1',
+                position: 1
+            })
+        );
+        assert_eq!(
+            "A]B[".parse::<Pattern>(),
+            Err(ParsePatternError::Unexpected {
+                symbol: ']',
+                position: 1
+            })
+        );
+        assert_eq!(
+            "TE[AO".parse::<Pattern>(),
+            Err(ParsePatternError::Unclosed { position: 2 })
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::str::FromStr;
+
+use crate::calculator::{
+    encoding::LetterEncoding,
+    operator::{Operation, OperationSet},
+};
+
+/**
+    Rules used to compute a numeric core
+
+    The [`Default`] rules are the ones used by the game :
+    - the first number starts the computation, the others are used in their input order
+    - subtraction, multiplication and division are each used once with 4 numbers
+    - intermediate values can't be negative
+    - divisions must be exact, using integer arithmetic
+    - the smallest result is the core
+    - letters are numbered from A = 1 to Z = 26
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CoreRules {
+    /// Operations available after the first number, each one usable ceil((numbers - 1) / operations) times, see [`OperationPool`](crate::calculator::operator::OperationPool)
+    pub operations: OperationSet,
+    /// Allows intermediate values below zero. The final core is never negative
+    pub allow_negative: bool,
+    /// How divisions with a remainder are handled
+    pub division: DivisionRule,
+    /// Whether intermediate values may be fractions
+    pub arithmetic: Arithmetic,
+    /// How the results of every operation order are combined into a single core
+    pub combine: CombineRule,
+    /// Allows the input numbers to be used in any order
+    pub reorder: bool,
+    /// How letters are converted to cypher numbers
+    pub encoding: LetterEncoding,
+}
+impl CoreRules {
+    /// Rules of the game
+    pub const STANDARD: Self = Self {
+        operations: OperationSet::empty()
+            .with(Operation::SUB)
+            .with(Operation::MUL)
+            .with(Operation::DIV),
+        allow_negative: false,
+        division: DivisionRule::Exact,
+        arithmetic: Arithmetic::Integer,
+        combine: CombineRule::Min,
+        reorder: false,
+        encoding: LetterEncoding::A1Z26,
+    };
+}
+impl Default for CoreRules {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+/// How divisions with a remainder are handled
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, derive_more::Display)]
+pub enum DivisionRule {
+    /// The operation order is discarded
+    #[default]
+    #[display("exact")]
+    Exact,
+    /// The remainder is dropped, rounding the result down
+    #[display("floor")]
+    Floor,
+    /// The whole group of numbers has no solution
+    #[display("reject")]
+    Reject,
+}
+impl FromStr for DivisionRule {
+    type Err = ParseRuleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "exact" => Ok(Self::Exact),
+            "floor" => Ok(Self::Floor),
+            "reject" => Ok(Self::Reject),
+            _ => Err(ParseRuleError {
+                expected: "exact, floor, reject",
+            }),
+        }
+    }
+}
+
+/**
+    Kind of numbers used for intermediate values
+
+    The final core is always a whole number
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, derive_more::Display)]
+pub enum Arithmetic {
+    /// Whole numbers only, divisions with a remainder follow the [`DivisionRule`]
+    #[default]
+    #[display("integer")]
+    Integer,
+    /// Exact fractions, divisions never have a remainder
+    #[display("rational")]
+    Rational,
+}
+impl Arithmetic {
+    /// The other arithmetic
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Integer => Self::Rational,
+            Self::Rational => Self::Integer,
+        }
+    }
+}
+impl FromStr for Arithmetic {
+    type Err = ParseRuleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "integer" => Ok(Self::Integer),
+            "rational" => Ok(Self::Rational),
+            _ => Err(ParseRuleError {
+                expected: "integer, rational",
+            }),
+        }
+    }
+}
+
+/// How the results of every operation order are combined into a single core
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, derive_more::Display)]
+pub enum CombineRule {
+    /// Smallest result
+    #[default]
+    #[display("min")]
+    Min,
+    /// Largest result
+    #[display("max")]
+    Max,
+    /// First valid result, operations being tried in the order of [`OPERATORS`](crate::calculator::operator::OPERATORS) : `- * / + % ^ & ~`
+    #[display("first")]
+    FirstValid,
+}
+impl CombineRule {
+    /// Merges a new `value` with the `best` value found so far
+    pub fn combine(self, best: Option<i128>, value: i128) -> i128 {
+        match (self, best) {
+            (_, None) => value,
+            (Self::Min, Some(best)) => best.min(value),
+            (Self::Max, Some(best)) => best.max(value),
+            (Self::FirstValid, Some(best)) => best,
+        }
+    }
+}
+impl FromStr for CombineRule {
+    type Err = ParseRuleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            "first" => Ok(Self::FirstValid),
+            _ => Err(ParseRuleError {
+                expected: "min, max, first",
+            }),
+        }
+    }
+}
+
+#[derive(Debug, derive_more::Display, derive_more::Error)]
+#[display("Invalid rule, expected one of : {expected}")]
+pub struct ParseRuleError {
+    pub(super) expected: &'static str,
+}
@@ -0,0 +1,123 @@
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+
+use crate::calculator::Letter;
+
+/// Combinations tried between two checks of the search limits
+const SEARCH_CHUNK: u64 = 1 << 14;
+
+/// Limits of a search that could otherwise run for a very long time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// Maximum number of results
+    pub max_results: usize,
+    /// Time after which the search stops, returning the results found so far
+    pub time_budget: Duration,
+}
+impl SearchLimits {
+    /// Limits that never stop a search
+    pub const UNLIMITED: Self = Self {
+        max_results: usize::MAX,
+        time_budget: Duration::MAX,
+    };
+}
+impl Default for SearchLimits {
+    fn default() -> Self {
+        Self {
+            max_results: 10_000,
+            time_budget: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Why a search stopped before trying every combination
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum SearchStop {
+    #[display("result cap reached")]
+    ResultCap,
+    #[display("time budget exceeded")]
+    TimeBudget,
+}
+
+/// Groups of numbers found by a search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberSearch {
+    /// Groups of numbers reaching the target, in lexicographic order
+    pub numbers: Vec<Vec<u32>>,
+    /// `None` if every combination was tried
+    pub stopped: Option<SearchStop>,
+}
+
+/// Words found by a search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordSearch {
+    /// Words reaching the target, in alphabetical order
+    pub words: Vec<Vec<Letter>>,
+    /// `None` if every combination was tried
+    pub stopped: Option<SearchStop>,
+}
+
+/**
+    Tries every combination of `0..combinations` in parallel, keeping the results of `test` in order
+
+    Combinations are tried by chunks, checking the `limits` between two chunks.
+    The search stops with [`SearchStop::ResultCap`] only once more than `max_results` results were found,
+    so a search with exactly `max_results` results is complete
+*/
+pub(super) fn search<T: Send>(
+    combinations: u64,
+    limits: &SearchLimits,
+    test: impl Fn(u64) -> Option<T> + Sync,
+) -> (Vec<T>, Option<SearchStop>) {
+    let start = Instant::now();
+    let mut output = Vec::new();
+    let mut chunk_start = 0;
+    while chunk_start < combinations {
+        if output.len() > limits.max_results {
+            break;
+        }
+        if start.elapsed() >= limits.time_budget {
+            return (output, Some(SearchStop::TimeBudget));
+        }
+        let chunk_end = chunk_start.saturating_add(SEARCH_CHUNK).min(combinations);
+        let found = (chunk_start..chunk_end)
+            .into_par_iter()
+            .filter_map(&test)
+            .collect::<Vec<_>>();
+        output.extend(found);
+        chunk_start = chunk_end;
+    }
+
+    let stopped = (output.len() > limits.max_results).then_some(SearchStop::ResultCap);
+    output.truncate(limits.max_results);
+    (output, stopped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits() {
+        let even = |index: u64| index.is_multiple_of(2).then_some(index);
+        let limits = SearchLimits {
+            max_results: 5,
+            ..Default::default()
+        };
+        assert_eq!(search(10, &limits, even), (vec![0, 2, 4, 6, 8], None));
+        assert_eq!(
+            search(11, &limits, even),
+            (vec![0, 2, 4, 6, 8], Some(SearchStop::ResultCap))
+        );
+
+        let limits = SearchLimits {
+            time_budget: Duration::ZERO,
+            ..Default::default()
+        };
+        assert_eq!(
+            search(10, &limits, even),
+            (vec![], Some(SearchStop::TimeBudget))
+        );
+    }
+}
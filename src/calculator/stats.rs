@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+use rayon::prelude::*;
+
+use crate::calculator::{
+    ALPHABET, CORE_LENGTHS,
+    decryptor::{DecryptError, OperationOrder, explain_numbers},
+    dictionary::Dictionary,
+    rules::CoreRules,
+    table::index_to_numbers,
+};
+
+/// Distribution of the cores of many groups of numbers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoreStats {
+    /// Groups of numbers decrypted
+    pub total: u64,
+    /// Groups reaching each core
+    pub cores: BTreeMap<u32, u64>,
+    /// Groups without any valid operation order
+    pub no_solution: u64,
+    /// Groups rejected by the rules, or too large to compute
+    pub other_errors: u64,
+    /// Groups won by each operation order, written with the symbols of its operations : `-*/`
+    pub orders: BTreeMap<String, u64>,
+    /// Groups whose core is produced by several operation orders
+    pub ties: u64,
+}
+impl CoreStats {
+    /// Decrypts a group of `numbers` and counts the result
+    fn add(&mut self, numbers: &[u32], rules: &CoreRules) {
+        let trace = explain_numbers(numbers, rules);
+        self.total += 1;
+        let core = match trace.core {
+            Ok(core) => core,
+            Err(DecryptError::NoSolution) => {
+                self.no_solution += 1;
+                return;
+            }
+            Err(_) => {
+                self.other_errors += 1;
+                return;
+            }
+        };
+        *self.cores.entry(core).or_default() += 1;
+
+        let winners = trace
+            .valid_orders()
+            .into_iter()
+            .filter(|order| order.core() == core)
+            .collect::<Vec<_>>();
+        if winners.len() > 1 {
+            self.ties += 1;
+        }
+        for symbols in winners.iter().map(symbols).unique() {
+            *self.orders.entry(symbols).or_default() += 1;
+        }
+    }
+
+    /// Sums the counts of two sweeps
+    fn merge(mut self, other: Self) -> Self {
+        self.total += other.total;
+        self.no_solution += other.no_solution;
+        self.other_errors += other.other_errors;
+        self.ties += other.ties;
+        for (core, count) in other.cores {
+            *self.cores.entry(core).or_default() += count;
+        }
+        for (symbols, count) in other.orders {
+            *self.orders.entry(symbols).or_default() += count;
+        }
+        self
+    }
+
+    /// Share of the groups counted by `count`, 0 if nothing was decrypted
+    pub fn share(&self, count: u64) -> f64 {
+        match self.total {
+            0 => 0.0,
+            total => count as f64 / total as f64,
+        }
+    }
+
+    /// Operation orders, the most frequent winner first
+    pub fn orders_by_wins(&self) -> Vec<(&str, u64)> {
+        self.orders
+            .iter()
+            .map(|(symbols, &count)| (symbols.as_str(), count))
+            .sorted_by_key(|&(symbols, count)| (std::cmp::Reverse(count), symbols))
+            .collect()
+    }
+}
+
+/// Symbols of the operations of an order, in the order they are applied
+fn symbols(order: &OperationOrder) -> String {
+    order.steps.iter().map(|step| step.op.symbol()).collect()
+}
+
+/**
+    Decrypts every word of `length` letters under the `rules`
+
+    Each extra letter multiplies the computation time by 26
+
+    # Errors
+    - `length` outside of [`CORE_LENGTHS`]
+*/
+pub fn alphabet_stats(length: usize, rules: &CoreRules) -> Result<CoreStats, DecryptError> {
+    if !CORE_LENGTHS.contains(&length) {
+        return Err(DecryptError::InputWordLen);
+    }
+    let base = ALPHABET.count() as u64;
+    let stats = (0..base.pow(length as u32))
+        .into_par_iter()
+        .fold(CoreStats::default, |mut stats, index| {
+            let mut numbers = index_to_numbers(index, length);
+            for number in &mut numbers[..length] {
+                *number = rules.encoding.number_at(*number);
+            }
+            stats.add(&numbers[..length], rules);
+            stats
+        })
+        .reduce(CoreStats::default, CoreStats::merge);
+    Ok(stats)
+}
+
+/// Decrypts every word of the `dictionary` that has `length` letters
+pub fn dictionary_stats(dictionary: &Dictionary, length: usize, rules: &CoreRules) -> CoreStats {
+    let words = dictionary
+        .words()
+        .filter(|word| word.len() == length)
+        .collect::<Vec<_>>();
+    words
+        .into_par_iter()
+        .fold(CoreStats::default, |mut stats, word| {
+            let numbers = word
+                .iter()
+                .map(|letter| rules.encoding.number(*letter))
+                .collect::<Vec<_>>();
+            stats.add(&numbers, rules);
+            stats
+        })
+        .reduce(CoreStats::default, CoreStats::merge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distributions() {
+        let rules = CoreRules::default();
+        assert_eq!(alphabet_stats(7, &rules), Err(DecryptError::InputWordLen));
+        let stats = alphabet_stats(3, &rules).unwrap();
+        assert_eq!(stats.total, 26 * 26 * 26);
+        assert_eq!(
+            stats.cores.values().sum::<u64>() + stats.no_solution + stats.other_errors,
+            stats.total
+        );
+        assert!(stats.ties > 0);
+        assert!(stats.orders.keys().all(|symbols| symbols.len() == 2));
+
+        let dictionary = Dictionary::parse("PEAK\nTREE\nSAND\nSAND\nCAT");
+        let stats = dictionary_stats(&dictionary, 4, &rules);
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.cores.get(&1), Some(&1));
+        assert_eq!(stats.share(stats.cores[&2]), 1.0 / 3.0);
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use rayon::prelude::*;
+
+use crate::calculator::{
+    ALPHABET, CORE_LENGTH, CORE_LENGTHS, Letter,
+    decryptor::{DecryptError, decrypt_numbers},
+    rules::CoreRules,
+};
+
+/// Longest supported core
+pub(super) const MAX_CORE_LENGTH: usize = *CORE_LENGTHS.end();
+
+/// Longest words stored in a table. Each extra letter multiplies the table size by 26
+pub const MAX_TABLE_LENGTH: usize = CORE_LENGTH;
+
+/// Tables already built, for each word length and rules
+type Tables = HashMap<(usize, CoreRules), Arc<CoreTable>>;
+static TABLES: LazyLock<Mutex<Tables>> = LazyLock::new(Default::default);
+
+/**
+    Precomputed core of every `length`-letter word, under a given set of rules
+
+    Words are indexed in alphabetical order : `AAAA` is 0, `AAAB` is 1...
+    Their letters are converted to numbers with the [`LetterEncoding`](crate::calculator::encoding::LetterEncoding) of the rules.
+    The inverse index lists the words producing each core
+*/
+#[derive(Debug)]
+pub struct CoreTable {
+    length: usize,
+    cores: Vec<Result<u32, DecryptError>>,
+    words: BTreeMap<u32, Vec<u32>>,
+}
+impl CoreTable {
+    /**
+        Table of the `length`-letter words under the `rules`
+
+        Built on first use, then shared. `None` if `length` is above [`MAX_TABLE_LENGTH`]
+    */
+    pub fn get(length: usize, rules: &CoreRules) -> Option<Arc<Self>> {
+        if !(*CORE_LENGTHS.start()..=MAX_TABLE_LENGTH).contains(&length) {
+            return None;
+        }
+        let mut tables = TABLES.lock().unwrap_or_else(|e| e.into_inner());
+        let table = tables
+            .entry((length, *rules))
+            .or_insert_with(|| Arc::new(Self::build(length, rules)));
+        Some(table.clone())
+    }
+
+    /// Same as [`CoreTable::get`], without building the table if it does not exist yet
+    pub fn cached(length: usize, rules: &CoreRules) -> Option<Arc<Self>> {
+        let tables = TABLES.lock().unwrap_or_else(|e| e.into_inner());
+        tables.get(&(length, *rules)).cloned()
+    }
+
+    fn build(length: usize, rules: &CoreRules) -> Self {
+        let base = ALPHABET.count() as u64;
+        let cores = (0..base.pow(length as u32))
+            .into_par_iter()
+            .map(|index| {
+                let mut numbers = index_to_numbers(index, length);
+                for number in &mut numbers[..length] {
+                    *number = rules.encoding.number_at(*number);
+                }
+                decrypt_numbers(&numbers[..length], rules)
+            })
+            .collect::<Vec<_>>();
+
+        let mut words = BTreeMap::<u32, Vec<u32>>::new();
+        for (index, core) in cores.iter().enumerate() {
+            if let Ok(core) = core {
+                words.entry(*core).or_default().push(index as u32);
+            }
+        }
+
+        Self {
+            length,
+            cores,
+            words,
+        }
+    }
+
+    /// Core of a word given as the positions of its letters in the alphabet, of the table's length
+    pub fn core(&self, positions: &[u32]) -> Result<u32, DecryptError> {
+        if positions.len() != self.length {
+            return Err(DecryptError::InputWordLen);
+        }
+        let base = ALPHABET.count();
+        let index = positions.iter().try_fold(0, |index, &position| {
+            Letter::try_from_num(position).ok_or(DecryptError::InputLetter)?;
+            Ok(index * base + (position - ALPHABET.start()) as usize)
+        })?;
+        self.cores[index].clone()
+    }
+
+    /// Every word producing the `core`, in alphabetical order
+    pub fn words(&self, core: u32) -> impl Iterator<Item = Vec<Letter>> {
+        let indices = self.words.get(&core).map(Vec::as_slice).unwrap_or_default();
+        indices.iter().map(|&index| {
+            index_to_numbers(index as u64, self.length)[..self.length]
+                .iter()
+                .filter_map(|&number| Letter::try_from_num(number))
+                .collect()
+        })
+    }
+}
+
+/// Positions in the alphabet of the letters of the word at `index` in alphabetical order. Only the first `length` numbers are used
+pub(super) fn index_to_numbers(mut index: u64, length: usize) -> [u32; MAX_CORE_LENGTH] {
+    let base = ALPHABET.count() as u64;
+    let mut numbers = [0; MAX_CORE_LENGTH];
+    for number in numbers[..length].iter_mut().rev() {
+        *number = ALPHABET.start() + (index % base) as u32;
+        index /= base;
+    }
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookups() {
+        let rules = CoreRules::default();
+        assert!(CoreTable::get(MAX_TABLE_LENGTH + 1, &rules).is_none());
+
+        let table = CoreTable::get(3, &rules).unwrap();
+        assert!(CoreTable::cached(3, &rules).is_some());
+        for numbers in [[1, 1, 1], [26, 2, 13], [20, 5, 4]] {
+            assert_eq!(table.core(&numbers), decrypt_numbers(&numbers, &rules));
+        }
+        assert_eq!(table.core(&[1, 2]), Err(DecryptError::InputWordLen));
+
+        let words = table.words(3).collect::<Vec<_>>();
+        assert!(!words.is_empty());
+        assert!(words.is_sorted());
+        for word in words {
+            let numbers = word
+                .iter()
+                .map(|letter| letter.to_num())
+                .collect::<Vec<_>>();
+            assert_eq!(decrypt_numbers(&numbers, &rules), Ok(3));
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::ops::Range;
+
+use crate::calculator::{
+    CORE_LENGTH,
+    decryptor::{DecryptError, decrypt_word},
+    encoding::LetterEncoding,
+    rules::CoreRules,
+};
+
+/// A run of letters in a free-form text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextToken {
+    /// Position of the first letter, counted in characters
+    pub offset: usize,
+    /// Position in the text, counted in bytes
+    pub span: Range<usize>,
+    pub word: String,
+    pub decoding: TokenDecoding,
+}
+
+/// Result of the decryption of a [`TextToken`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenDecoding {
+    Core(Result<u32, DecryptError>),
+    /// The token is not a [`CORE_LENGTH`]-letter word
+    Skipped,
+}
+
+/**
+    Splits a `text` into runs of letters, with their offset counted in characters and their span in bytes
+
+    Everything else is a separator
+*/
+pub fn tokenize(text: &str) -> Vec<(usize, Range<usize>)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (offset, (index, c)) in text.char_indices().enumerate() {
+        match (c.is_alphabetic(), start) {
+            (true, None) => start = Some((offset, index)),
+            (false, Some((offset, start_index))) => {
+                tokens.push((offset, start_index..index));
+                start = None;
+            }
+            _ => (),
+        }
+    }
+    if let Some((offset, start_index)) = start {
+        tokens.push((offset, start_index..text.len()));
+    }
+    tokens
+}
+
+/**
+    Decrypts every [`CORE_LENGTH`]-letter word of a free-form `text`, like a note found in the game
+
+    Punctuation, digits and whitespace separate the words. Words of other lengths are skipped
+*/
+pub fn decrypt_text(text: &str, rules: &CoreRules) -> Vec<TextToken> {
+    tokenize(text)
+        .into_iter()
+        .map(|(offset, span)| {
+            let word = &text[span.clone()];
+            let decoding = match word.chars().count() == CORE_LENGTH {
+                true => TokenDecoding::Core(decrypt_word(word, rules)),
+                false => TokenDecoding::Skipped,
+            };
+            TextToken {
+                offset,
+                span,
+                word: word.to_string(),
+                decoding,
+            }
+        })
+        .collect()
+}
+
+impl TokenDecoding {
+    /// Decoded letter using the `encoding`, the core if it is not a letter, the error, or `skipped`
+    pub fn text(&self, encoding: LetterEncoding) -> String {
+        match self {
+            Self::Core(Ok(core)) => match encoding.letter(*core) {
+                Some(letter) => letter.to_string(),
+                None => core.to_string(),
+            },
+            Self::Core(Err(error)) => error.to_string(),
+            Self::Skipped => "skipped".to_string(),
+        }
+    }
+}
+
+/// Copy of the original `text`, each token followed by its offset and decoding : `PIGS[@5 S]`
+pub fn annotate(text: &str, tokens: &[TextToken], encoding: LetterEncoding) -> String {
+    let mut output = String::with_capacity(text.len() * 2);
+    let mut copied = 0;
+    for token in tokens {
+        output.push_str(&text[copied..token.span.end]);
+        let decoding = token.decoding.text(encoding);
+        output.push_str(&format!("[@{} {decoding}]", token.offset));
+        copied = token.span.end;
+    }
+    output.push_str(&text[copied..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn free_text() {
+        let text = "Dear PIGS,\n  the SAND! 42";
+        let tokens = decrypt_text(text, &CoreRules::default());
+        let words = tokens
+            .iter()
+            .map(|token| token.word.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(words, ["Dear", "PIGS", "the", "SAND"]);
+        assert_eq!(tokens[1].offset, 5);
+        assert_eq!(tokens[2].decoding, TokenDecoding::Skipped);
+        assert_eq!(tokens[3].decoding, TokenDecoding::Core(Ok(20)));
+        assert_eq!(
+            annotate(text, &tokens, LetterEncoding::A1Z26),
+            "Dear[@0 B] PIGS[@5 S],\n  the[@13 skipped] SAND[@17 T]! 42"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::calculator::{
+    Letter,
+    decryptor::{DecryptError, OperationOrder, decrypt_word, explain_word},
+    rules::CoreRules,
+};
+
+/// A word and the letter it is claimed to decode to, written `WORD=LETTER`
+///
+/// Only ASCII letters are uppercased, accented letters are left to [`Language::fold`](crate::calculator::language::Language::fold)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claim {
+    pub word: String,
+    pub letter: Letter,
+}
+impl Claim {
+    /// Decodes the word and compares its core with the claimed letter
+    pub fn verify(&self, rules: &CoreRules) -> Verdict {
+        let core = match decrypt_word(&self.word, rules) {
+            Ok(core) => core,
+            Err(error) => return Verdict::Failed(error),
+        };
+        if core == rules.encoding.number(self.letter) {
+            return Verdict::Confirmed;
+        }
+        let orders = explain_word(&self.word, rules)
+            .map(|trace| trace.valid_orders())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|order| order.core() == core)
+            .collect();
+        Verdict::Mismatch { core, orders }
+    }
+}
+impl Display for Claim {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.word, self.letter)
+    }
+}
+impl FromStr for Claim {
+    type Err = ParseClaimError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (word, letter) = s.split_once('=').ok_or(ParseClaimError)?;
+        let word = word.trim();
+        if word.is_empty() {
+            return Err(ParseClaimError);
+        }
+        let mut letter = letter.trim().chars();
+        match (letter.next().and_then(Letter::try_from_char), letter.next()) {
+            (Some(letter), None) => Ok(Self {
+                word: word.to_ascii_uppercase(),
+                letter,
+            }),
+            _ => Err(ParseClaimError),
+        }
+    }
+}
+
+#[derive(Debug, derive_more::Display, derive_more::Error)]
+#[display("Invalid claim, expected WORD=LETTER like PEAK=A")]
+pub struct ParseClaimError;
+
+/// Outcome of the check of a [`Claim`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    /// The word decodes to the claimed letter
+    Confirmed,
+    /// The word decodes to another core, produced by each of the `orders`
+    Mismatch {
+        core: u32,
+        orders: Vec<OperationOrder>,
+    },
+    /// The word can't be decoded
+    Failed(DecryptError),
+}
+
+/**
+    Reads a list of claims, one `WORD=LETTER` per line
+
+    Empty lines and lines starting with `#` are ignored.
+    Each claim comes with its line number, starting at 1
+*/
+pub fn parse_claims(text: &str) -> impl Iterator<Item = (usize, Result<Claim, ParseClaimError>)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| (number, line.parse()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claims() {
+        let rules = CoreRules::default();
+        let claims = parse_claims("# word=letter\npeak = a\n\nSAND=B\nPIGS\nHEAT=AB")
+            .map(|(number, claim)| (number, claim.ok()))
+            .collect::<Vec<_>>();
+        assert_eq!(claims.len(), 4);
+        assert_eq!(claims[2], (5, None));
+        assert_eq!(claims[3], (6, None));
+
+        let (_, Some(peak)) = &claims[0] else {
+            panic!("expected a claim");
+        };
+        assert_eq!(peak.to_string(), "PEAK=A");
+        assert_eq!(peak.verify(&rules), Verdict::Confirmed);
+
+        let (4, Some(sand)) = &claims[1] else {
+            panic!("expected a claim on line 4");
+        };
+        let Verdict::Mismatch { core, orders } = sand.verify(&rules) else {
+            panic!("SAND decodes to T");
+        };
+        assert_eq!(core, 20);
+        assert!(!orders.is_empty());
+        assert!(orders.iter().all(|order| order.core() == 20));
+
+        let claim = "straße=A".parse::<Claim>().unwrap();
+        assert_eq!(
+            claim.word, "STRAßE",
+            "ß is folded by the language, not uppercased"
+        );
+
+        let claim = "TICKETS=A".parse::<Claim>().unwrap();
+        assert_eq!(
+            claim.verify(&rules),
+            Verdict::Failed(DecryptError::InputWordLen)
+        );
+    }
+}
@@ -1,11 +1,31 @@
-use std::str::FromStr;
+use std::{ops::RangeInclusive, path::Path, str::FromStr, time::Duration};
 
 use clap::{Parser, Subcommand};
+use itertools::Itertools;
 
 use crate::calculator::{
-    Letter,
-    decryptor::{DecryptInput, decrypt_numbers, decrypt_word},
-    encryptor::encrypt_letter,
+    CORE_LENGTH, CORE_LENGTHS, Letter,
+    decryptor::{
+        CoreCandidate, DecryptError, DecryptInput, DecryptTrace, InputError, TraceBranch,
+        WildcardCore, WildcardSearch, decrypt_all, decrypt_nested, decrypt_numbers_with_arithmetic,
+        decrypt_wildcard_word, decrypt_word_with_arithmetic, explain_numbers, explain_word,
+        has_wildcards, solve_numbers, word_to_numbers,
+    },
+    dictionary::{Dictionary, SortOrder},
+    encoding::LetterEncoding,
+    encryptor::{encrypt_letter, encrypt_number},
+    expression::{Expression, ParseExpressionError},
+    fraction::Fraction,
+    interpret::{Readings, interpret},
+    language::Language,
+    message::{PickOptions, encrypt_message},
+    operator::OperationSet,
+    pattern::Pattern,
+    rules::{Arithmetic, CombineRule, CoreRules, DivisionRule},
+    search::SearchLimits,
+    stats::{alphabet_stats, dictionary_stats},
+    text::{TokenDecoding, annotate, decrypt_text},
+    verify::{Claim, Verdict, parse_claims},
 };
 
 /// Blue Prince numeric core calculator
@@ -17,80 +37,551 @@ use crate::calculator::{
 pub struct Args {
     #[command(subcommand)]
     pub command: Option<Command>,
+    /// Word lists used by the Terminal UI, one word per line.
+    /// `english` is the list embedded with the `english-dictionary` feature, used by default
+    #[arg(long, value_name = "FILE")]
+    pub dict: Vec<String>,
+    /// How letters are converted to numbers in the Terminal UI: a1z26, a0z25, z1a26, keypad
+    #[arg(long, value_name = "ENCODING", default_value_t, value_parser = LetterEncoding::from_str)]
+    pub encoding: LetterEncoding,
+    /// Language of the Terminal UI inputs, their accented letters are folded: english, french, german
+    #[arg(long, value_name = "LANGUAGE", default_value_t, value_parser = Language::from_str)]
+    pub language: Language,
 }
 
 /// Parse application arguments using [`clap`]
 ///
 /// # Exits
 /// [`clap`] will exit the program if parsing fails
-pub fn parse_command_or_exit() -> Option<Command> {
-    Args::parse().command
+pub fn parse_args_or_exit() -> Args {
+    Args::parse()
+}
+
+/// Loads and merges word lists, `english` being the embedded list. `None` if there are no `sources`
+pub fn load_dictionary(sources: &[String]) -> Result<Option<Dictionary>, String> {
+    if sources.is_empty() {
+        return Ok(None);
+    }
+    let mut dictionary = Dictionary::default();
+    for source in sources {
+        let words = match source.as_str() {
+            "english" => Dictionary::english()
+                .ok_or("The english dictionary requires the english-dictionary feature")?,
+            path => Dictionary::load(Path::new(path)).map_err(|e| e.to_string())?,
+        };
+        dictionary.merge(words);
+    }
+    Ok(Some(dictionary))
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Command {
-    /// Computes every 4-letter word for a given letter
+    /// Computes every 4-letter word for a given letter, or groups of numbers for a numeric core
     #[command(name = "encode")]
-    Encrypt {
-        /// Alphabetic letter in the range [A-Z] or [a-z]
-        /// Examples:
-        ///     encode D   
-        ///     encode N
-        ///
-        /// Tip: output tend to be long (2000~6000 lines), it is recommended to pipe the output into a file
-        ///     encode L > file.txt
-        #[arg(value_name = "LETTER", value_parser = Letter::from_str, verbatim_doc_comment)]
-        letter: Letter,
-    },
+    Encrypt(EncryptArgs),
     /// Computes numeric cores from a given cyphertext
     #[command(name = "decode")]
-    Decrypt {
-        /// Can be either:
-        ///     <WORDS>       one or more 4-letter words (case insensitive) separated by spaces
-        ///     <4-NUMBERS>   4 numbers separated by spaces
-        ///
-        /// Examples:
-        ///     decode "CLAM tell FIND"
-        ///     decode "156 21 9 7
-        #[arg(value_name = "WORDS or 4-NUMBERS", value_parser = DecryptInput::from_str, verbatim_doc_comment)]
-        input: DecryptInput,
-    },
+    Decrypt(DecryptArgs),
+    /// Decrypts every word of a given length and reports the distribution of their cores
+    Stats(StatsArgs),
+    /// Checks that words decode to the letters they are claimed to, exits with an error on any mismatch
+    Verify(VerifyArgs),
+}
+
+#[derive(clap::Args, Debug)]
+pub struct EncryptArgs {
+    /// Alphabetic letter in the range [A-Z] or [a-z]
+    /// Examples:
+    ///     encode D   
+    ///     encode N
+    ///
+    /// Tip: output tend to be long (2000~6000 lines), it is recommended to pipe the output into a file
+    ///     encode L > file.txt
+    #[arg(value_name = "LETTER", value_parser = Letter::from_str, required_unless_present_any = ["target", "message"], verbatim_doc_comment)]
+    letter: Option<Letter>,
+    /// Numeric core to reach instead of a letter
+    #[arg(long, value_name = "CORE", conflicts_with = "letter")]
+    target: Option<u32>,
+    /// Plaintext message to encrypt, prints the candidate words of each letter
+    #[arg(long, value_name = "TEXT", conflicts_with_all = ["letter", "target", "numbers"])]
+    message: Option<String>,
+    /// Picks a single cyphertext for the message, the same seed always gives the same cyphertext
+    #[arg(long, value_name = "NUMBER", requires = "message")]
+    seed: Option<u64>,
+    /// Never uses the same word twice in the cyphertext
+    #[arg(long, requires = "seed")]
+    unique: bool,
+    /// Word lists, one word per line. Dictionary words are marked with a '*', and picked first in a cyphertext.
+    /// `english` is the list embedded with the `english-dictionary` feature
+    #[arg(long, value_name = "FILE")]
+    dict: Vec<String>,
+    /// Only prints dictionary words
+    #[arg(long, requires = "dict")]
+    hits_only: bool,
+    /// Order of the words: alpha, frequency (most frequent dictionary words first)
+    #[arg(long, value_name = "ORDER", default_value_t, value_parser = SortOrder::from_str)]
+    sort: SortOrder,
+    /// Prints groups of numbers instead of words
+    #[arg(long)]
+    numbers: bool,
+    /// Numbers to pick from, like 1..=100 or 1..101. Defaults to the numbers of the letters under the --encoding
+    #[arg(long, value_name = "RANGE", value_parser = parse_range, requires = "numbers")]
+    range: Option<RangeInclusive<u32>>,
+    /// Length of the computed words, from 3 to 6 letters.
+    /// Each extra letter multiplies the computation time by 26
+    #[arg(long, value_name = "LETTERS", default_value_t = CORE_LENGTH, value_parser = parse_core_length)]
+    length: usize,
+    /// Only computes words matching the pattern: a letter for a fixed slot, '?' for any letter,
+    /// or a set of letters like [AEIOU]
+    /// Example:
+    ///     encode L --pattern "T??E"
+    #[arg(long, value_parser = Pattern::from_str, conflicts_with_all = ["length", "target", "message"], verbatim_doc_comment)]
+    pattern: Option<Pattern>,
+    /// Stops the search after this many results
+    #[arg(long, value_name = "COUNT", default_value_t = SearchLimits::default().max_results)]
+    max_results: usize,
+    /// Stops the search after this many seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = SearchLimits::default().time_budget.as_secs())]
+    time_budget: u64,
+    /// Language of the message, its accented letters are folded: english, french, german
+    #[arg(long, value_name = "LANGUAGE", default_value_t, value_parser = Language::from_str, requires = "message")]
+    language: Language,
+    #[command(flatten)]
+    rules: RulesArgs,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct DecryptArgs {
+    /// Can be either:
+    ///     <WORDS>       one or more 4-letter words (case insensitive) separated by spaces
+    ///     <4-NUMBERS>   4 numbers separated by spaces
+    ///
+    /// Words and groups of 3 to 6 numbers are also accepted
+    /// Several groups of numbers can be separated by , ; | or new lines
+    /// Unknown numbers are written `?` and solved with --target
+    ///
+    /// Examples:
+    ///     decode "CLAM tell FIND"
+    ///     decode "156 21 9 7"
+    ///     decode "34 ? 22 4" --target 19
+    #[arg(value_name = "WORDS or 4-NUMBERS", verbatim_doc_comment)]
+    input: String,
+    /// Prints every operation order that was tried, and why dead branches were abandoned
+    #[arg(long)]
+    explain: bool,
+    /// Prints every distinct core that can be reached, with each operation order producing it
+    #[arg(long, conflicts_with = "explain")]
+    all_orders: bool,
+    /// Regroups the decoded letters into words and decodes them again, until it can go no further
+    #[arg(long, conflicts_with_all = ["explain", "all_orders"])]
+    nested: bool,
+    /// Decodes every 4-letter word of a free-form text, keeping its layout and punctuation.
+    /// Each word is followed by its character offset and decoded letter, other words are skipped
+    #[arg(long, conflicts_with_all = ["explain", "all_orders", "nested"])]
+    text: bool,
+    /// Evaluates an arithmetic expression like "((34-4)*22)/67" under the rules,
+    /// and tells whether it is one of the operation orders tried by the decryption
+    #[arg(long, conflicts_with_all = ["explain", "all_orders", "nested", "text"])]
+    expr: bool,
+    /// Alternative readings of cores that are not a letter, as a comma separated list:
+    ///     modulo   wraps around the alphabet: 27 is A, 0 is Z
+    ///     pairs    splits the digits into pairs: 1226 is L Z
+    ///     zero     counts from A = 0: 0 is A
+    ///     all      every reading above
+    #[arg(long, value_name = "LIST", default_value_t, value_parser = Readings::from_str, verbatim_doc_comment)]
+    readings: Readings,
+    /// Language of the input, its accented letters are folded: english, french, german
+    #[arg(long, value_name = "LANGUAGE", default_value_t, value_parser = Language::from_str)]
+    language: Language,
+    /// Prints every completion of the unknown numbers `?` that decrypts to this core
+    #[arg(long, value_name = "CORE", conflicts_with_all = ["explain", "all_orders", "nested", "text", "expr"])]
+    target: Option<u32>,
+    /// Numbers to pick the unknown numbers from, like 1..=100 or 1..101. Defaults to the numbers of the letters under the --encoding
+    #[arg(long, value_name = "RANGE", value_parser = parse_range, requires = "target")]
+    range: Option<RangeInclusive<u32>>,
+    /// Stops the search of unknown numbers or letters after this many results
+    #[arg(long, value_name = "COUNT", default_value_t = SearchLimits::default().max_results)]
+    max_results: usize,
+    /// Stops the search of unknown numbers or letters after this many seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = SearchLimits::default().time_budget.as_secs())]
+    time_budget: u64,
+    #[command(flatten)]
+    rules: RulesArgs,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct VerifyArgs {
+    /// Words and the letter they are claimed to decode to
+    /// Example:
+    ///     verify PEAK=A SAND=T
+    #[arg(value_name = "WORD=LETTER", value_parser = Claim::from_str, required_unless_present = "file", verbatim_doc_comment)]
+    claims: Vec<Claim>,
+    /// Reads claims from files, one WORD=LETTER per line.
+    /// Empty lines and lines starting with # are ignored
+    #[arg(long, value_name = "FILE")]
+    file: Vec<String>,
+    /// Language of the words, their accented letters are folded: english, french, german
+    #[arg(long, value_name = "LANGUAGE", default_value_t, value_parser = Language::from_str)]
+    language: Language,
+    #[command(flatten)]
+    rules: RulesArgs,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct StatsArgs {
+    /// Decrypts the words of these lists instead of every combination of letters.
+    /// `english` is the list embedded with the `english-dictionary` feature
+    #[arg(long, value_name = "FILE")]
+    dict: Vec<String>,
+    /// Length of the words, from 3 to 6 letters.
+    /// Each extra letter multiplies the computation time by 26 without a dictionary
+    #[arg(long, value_name = "LETTERS", default_value_t = CORE_LENGTH, value_parser = parse_core_length)]
+    length: usize,
+    /// Output format: table, csv
+    #[arg(long, value_name = "FORMAT", default_value_t, value_parser = parse_stats_format)]
+    format: StatsFormat,
+    #[command(flatten)]
+    rules: RulesArgs,
+}
+
+/// Output of the `stats` command
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+enum StatsFormat {
+    /// Aligned columns
+    #[default]
+    #[display("table")]
+    Table,
+    /// `section,key,count,share` rows
+    #[display("csv")]
+    Csv,
+}
+fn parse_stats_format(s: &str) -> Result<StatsFormat, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "table" => Ok(StatsFormat::Table),
+        "csv" => Ok(StatsFormat::Csv),
+        _ => Err("expected table or csv".to_string()),
+    }
+}
+
+/// Puzzle rules, the defaults are the rules of the game
+#[derive(clap::Args, Debug)]
+pub struct RulesArgs {
+    /// Operations available after the first number, each one usable ceil((numbers - 1) / operations) times,
+    /// so once with the 4 numbers and 3 operations of the game:
+    ///     + - * /   the four operations
+    ///     %         remainder of a division
+    ///     ^         exponentiation
+    ///     &         digit concatenation: 34 & 4 is 344
+    ///     ~         absolute difference
+    #[arg(long, value_name = "SYMBOLS", default_value_t = CoreRules::STANDARD.operations, value_parser = OperationSet::from_str, verbatim_doc_comment)]
+    ops: OperationSet,
+    /// Allows intermediate values below zero
+    #[arg(long)]
+    allow_negative: bool,
+    /// How divisions with a remainder are handled: exact, floor, reject
+    #[arg(long, value_name = "RULE", default_value_t, value_parser = DivisionRule::from_str)]
+    division: DivisionRule,
+    /// Kind of intermediate values: integer, rational (exact fractions, the final core is still a whole number)
+    #[arg(long, value_name = "KIND", default_value_t, value_parser = Arithmetic::from_str)]
+    arithmetic: Arithmetic,
+    /// How the results of every operation order are combined: min, max, first
+    #[arg(long, value_name = "RULE", default_value_t, value_parser = CombineRule::from_str)]
+    combine: CombineRule,
+    /// Allows the input numbers to be used in any order
+    #[arg(long)]
+    reorder: bool,
+    /// How letters are converted to numbers: a1z26, a0z25, z1a26, keypad (phone keys, ABC is 2)
+    #[arg(long, value_name = "ENCODING", default_value_t, value_parser = LetterEncoding::from_str)]
+    encoding: LetterEncoding,
+}
+/// Parses a range of numbers, `1..=100` or `1..101`
+fn parse_range(s: &str) -> Result<RangeInclusive<u32>, String> {
+    let error = || "expected a range like 1..=100 or 1..101".to_string();
+    let (start, end) = s.split_once("..").ok_or_else(error)?;
+    let start = start.trim().parse().map_err(|_| error())?;
+    let end = match end.strip_prefix('=') {
+        Some(end) => end.trim().parse().map_err(|_| error())?,
+        None => {
+            let end: u32 = end.trim().parse().map_err(|_| error())?;
+            end.checked_sub(1).ok_or_else(error)?
+        }
+    };
+    Ok(start..=end)
+}
+
+/// Parses a word length within [`CORE_LENGTHS`]
+fn parse_core_length(s: &str) -> Result<usize, String> {
+    s.parse()
+        .ok()
+        .filter(|length| CORE_LENGTHS.contains(length))
+        .ok_or_else(|| {
+            let (min, max) = CORE_LENGTHS.into_inner();
+            format!("expected a length from {min} to {max}")
+        })
+}
+
+impl From<RulesArgs> for CoreRules {
+    fn from(args: RulesArgs) -> Self {
+        CoreRules {
+            operations: args.ops,
+            allow_negative: args.allow_negative,
+            division: args.division,
+            arithmetic: args.arithmetic,
+            combine: args.combine,
+            reorder: args.reorder,
+            encoding: args.encoding,
+        }
+    }
 }
 
 pub fn run(command: Command) -> Result<(), String> {
     match command {
-        Command::Encrypt { letter } => encrypt(letter),
-        Command::Decrypt { input } => decrypt(input),
+        Command::Encrypt(args) => encrypt(args),
+        Command::Decrypt(args) => decrypt(args),
+        Command::Stats(args) => stats(args),
+        Command::Verify(args) => verify(args),
     }
 }
 
-fn encrypt(letter: Letter) -> Result<(), String> {
-    let cores = encrypt_letter(letter);
-    for core in cores {
-        println!("{}{}{}{}", core[0], core[1], core[2], core[3])
+fn encrypt(args: EncryptArgs) -> Result<(), String> {
+    let EncryptArgs {
+        letter,
+        target,
+        message,
+        seed,
+        unique,
+        dict,
+        hits_only,
+        sort,
+        numbers,
+        range,
+        length,
+        pattern,
+        max_results,
+        time_budget,
+        language,
+        rules,
+    } = args;
+    let rules = &rules.into();
+    let dictionary = load_dictionary(&dict)?;
+    if sort == SortOrder::Frequency && !dictionary.as_ref().is_some_and(Dictionary::has_counts) {
+        return Err("--sort frequency needs a word list with counts, like `HEAD 900`".to_string());
+    }
+    // Word as printed, `None` if it is filtered out
+    let word_text = |word: &[Letter]| {
+        let text = word.iter().copied().collect::<String>();
+        match dictionary
+            .as_ref()
+            .map(|dictionary| dictionary.contains(word))
+        {
+            None => Some(text),
+            Some(true) => Some(text + " *"),
+            Some(false) => (!hits_only).then_some(text),
+        }
+    };
+
+    if let Some(message) = message {
+        let message = language.fold(&message);
+        let candidates = encrypt_message(&message, length, rules).map_err(|e| e.to_string())?;
+        let Some(seed) = seed else {
+            for letter in candidates.words.iter().flatten() {
+                let mut words = letter.words.clone();
+                sort.sort(&mut words, dictionary.as_ref());
+                let words = words.iter().filter_map(|word| word_text(word));
+                println!("{} - {}", letter.letter, words.format(", "));
+            }
+            return Ok(());
+        };
+        let options = PickOptions {
+            unique,
+            preferred: dictionary.as_ref(),
+            seed,
+        };
+        let cyphertext = candidates.pick(&options).map_err(|e| e.to_string())?;
+        println!("{cyphertext}");
+        return Ok(());
+    }
+
+    let limits = SearchLimits {
+        max_results,
+        time_budget: Duration::from_secs(time_budget),
+    };
+    if let Some(letter) = letter
+        && !numbers
+    {
+        let pattern = pattern.unwrap_or_else(|| Pattern::any(length));
+        let mut search =
+            encrypt_letter(letter, &pattern, rules, &limits).map_err(|e| e.to_string())?;
+        sort.sort(&mut search.words, dictionary.as_ref());
+        for text in search.words.iter().filter_map(|word| word_text(word)) {
+            println!("{text}")
+        }
+        if let Some(stopped) = search.stopped {
+            eprintln!(
+                "Search stopped, {stopped} after {} results",
+                search.words.len()
+            );
+        }
+        return Ok(());
+    }
+
+    let target = target
+        .or(letter.map(|letter| rules.encoding.number(letter)))
+        .ok_or("Expected a letter or a target")?;
+    let range = range.unwrap_or_else(|| rules.encoding.numbers());
+    let search =
+        encrypt_number(target, range, length, rules, &limits).map_err(|e| e.to_string())?;
+    if numbers {
+        for group in &search.numbers {
+            println!("{}", group.iter().format(" "));
+        }
+    } else {
+        let mut words = search
+            .numbers
+            .iter()
+            .filter_map(|group| rules.encoding.word(group))
+            .collect::<Vec<_>>();
+        sort.sort(&mut words, dictionary.as_ref());
+        for text in words.iter().filter_map(|word| word_text(word)) {
+            println!("{text}");
+        }
+    }
+    if let Some(stopped) = search.stopped {
+        eprintln!(
+            "Search stopped, {stopped} after {} results",
+            search.numbers.len()
+        );
     }
     Ok(())
 }
 
-fn decrypt(input: DecryptInput) -> Result<(), String> {
-    match input {
-        DecryptInput::Numbers(numbers) => {
-            let core = decrypt_numbers(numbers).map_err(|e| e.to_string())?;
-            println!("{core}");
+fn decrypt(args: DecryptArgs) -> Result<(), String> {
+    let DecryptArgs {
+        input,
+        explain,
+        all_orders,
+        nested,
+        text,
+        expr,
+        readings,
+        language,
+        target,
+        range,
+        max_results,
+        time_budget,
+        rules,
+    } = args;
+    let rules = &rules.into();
+    let input = language.fold(&input);
+    if expr {
+        let expression = input
+            .parse()
+            .map_err(|e: ParseExpressionError| e.to_string())?;
+        return evaluate(&expression, rules);
+    }
+    if text {
+        let tokens = decrypt_text(&input, rules);
+        println!("{}", annotate(&input, &tokens, rules.encoding));
+        let letters = tokens
+            .iter()
+            .filter(|token| token.decoding != TokenDecoding::Skipped)
+            .map(|token| match token.decoding {
+                TokenDecoding::Core(Ok(_)) => token.decoding.text(rules.encoding),
+                _ => "?".to_string(),
+            });
+        println!("Decoded : {}", letters.format(" "));
+        return Ok(());
+    }
+    let parsed = input.parse().map_err(|e: InputError| {
+        eprintln!("{}", e.diagnostic(&input));
+        e.to_string()
+    })?;
+    let limits = SearchLimits {
+        max_results,
+        time_budget: Duration::from_secs(time_budget),
+    };
+    if let Some(target) = target {
+        let range = range.unwrap_or_else(|| rules.encoding.numbers());
+        return solve(parsed, target, range, rules, &limits);
+    }
+    match parsed {
+        DecryptInput::Partial(_) => Err("Unknown numbers `?` need a --target core".to_string()),
+        DecryptInput::Numbers(groups) => {
+            let single = groups.len() == 1;
+            let mut errors = Vec::new();
+            for numbers in groups {
+                let label = match single {
+                    true => String::new(),
+                    false => format!("{} - ", numbers.iter().format(" ")),
+                };
+                if explain {
+                    print_trace(&explain_numbers(&numbers, rules));
+                }
+                if all_orders {
+                    print_candidates(&decrypt_all(&numbers, rules), rules.encoding);
+                }
+                match decrypt_numbers_with_arithmetic(&numbers, rules) {
+                    Ok(core) => println!(
+                        "{label}{core}{}",
+                        readings_text(core.value, readings, rules.encoding)
+                    ),
+                    Err(e) if single => return Err(e.to_string()),
+                    Err(e) => {
+                        println!("{label}{e}");
+                        errors.push(numbers.iter().join(" "));
+                    }
+                }
+            }
+            match errors.is_empty() {
+                true => Ok(()),
+                false => Err(format!("Failed to decrypt : {}", errors.join(", "))),
+            }
+        }
+        DecryptInput::Words(words) if nested => {
+            for level in decrypt_nested(&words, rules) {
+                println!(
+                    "{} -> {}",
+                    level.words.join(" "),
+                    level.text(rules.encoding)
+                );
+            }
             Ok(())
         }
         DecryptInput::Words(words) => {
             let mut errors = Vec::new();
-            for word in words {
-                match decrypt_word(&word) {
+            for (index, word) in words.into_iter().enumerate() {
+                if has_wildcards(&word) {
+                    match decrypt_wildcard_word(&word, rules, &limits) {
+                        Ok(search) => {
+                            print_wildcard_cores(&word, &search, readings, rules.encoding)
+                        }
+                        Err(e) => {
+                            print_word_error(&input, index, e);
+                            errors.push(word)
+                        }
+                    }
+                    continue;
+                }
+                if explain && let Ok(trace) = explain_word(&word, rules) {
+                    println!("{word}");
+                    print_trace(&trace);
+                }
+                if all_orders && let Ok(numbers) = word_to_numbers(&word, rules.encoding) {
+                    println!("{word}");
+                    print_candidates(&decrypt_all(&numbers, rules), rules.encoding);
+                }
+                match decrypt_word_with_arithmetic(&word, rules) {
                     Ok(core) => println!(
-                        "{} - {core}",
-                        Letter::try_from_num(core)
+                        "{} - {core}{}",
+                        rules
+                            .encoding
+                            .letter(core.value)
                             .map(Letter::to_char)
-                            .unwrap_or('?')
+                            .unwrap_or('?'),
+                        readings_text(core.value, readings, rules.encoding)
                     ),
                     Err(e) => {
-                        println!("{e}");
+                        print_word_error(&input, index, e);
                         errors.push(word)
                     }
                 }
@@ -107,3 +598,303 @@ fn decrypt(input: DecryptInput) -> Result<(), String> {
         }
     }
 }
+
+fn stats(args: StatsArgs) -> Result<(), String> {
+    let StatsArgs {
+        dict,
+        length,
+        format,
+        rules,
+    } = args;
+    let rules = &rules.into();
+    let stats = match load_dictionary(&dict)? {
+        Some(dictionary) => dictionary_stats(&dictionary, length, rules),
+        None => alphabet_stats(length, rules).map_err(|e| e.to_string())?,
+    };
+    let letter = |core: u32| {
+        rules
+            .encoding
+            .letter(core)
+            .map_or(String::new(), |letter| letter.to_string())
+    };
+
+    match format {
+        StatsFormat::Csv => {
+            println!("section,key,count,share");
+            let summary = [
+                ("total", stats.total),
+                ("no_solution", stats.no_solution),
+                ("other_errors", stats.other_errors),
+                ("ties", stats.ties),
+            ];
+            for (key, count) in summary {
+                println!("summary,{key},{count},{}", stats.share(count));
+            }
+            for (&core, &count) in &stats.cores {
+                println!("core,{core},{count},{}", stats.share(count));
+            }
+            for (symbols, count) in stats.orders_by_wins() {
+                println!("order,{symbols},{count},{}", stats.share(count));
+            }
+        }
+        StatsFormat::Table => {
+            let percent = |count| format!("{:.2}%", stats.share(count) * 100.0);
+            println!("Words        : {} of {length} letters", stats.total);
+            println!(
+                "No solution  : {} ({})",
+                stats.no_solution,
+                percent(stats.no_solution)
+            );
+            println!(
+                "Other errors : {} ({})",
+                stats.other_errors,
+                percent(stats.other_errors)
+            );
+            println!(
+                "Ties         : {} ({}) reached by several operation orders",
+                stats.ties,
+                percent(stats.ties)
+            );
+            println!();
+            println!(
+                "{:>6}  {:<6}  {:>8}  {:>7}",
+                "Core", "Letter", "Words", "Share"
+            );
+            for (&core, &count) in &stats.cores {
+                println!(
+                    "{core:>6}  {:<6}  {count:>8}  {:>7}",
+                    letter(core),
+                    percent(count)
+                );
+            }
+            println!();
+            println!("{:<8}  {:>8}  {:>7}", "Order", "Wins", "Share");
+            for (symbols, count) in stats.orders_by_wins() {
+                println!("{symbols:<8}  {count:>8}  {:>7}", percent(count));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn verify(args: VerifyArgs) -> Result<(), String> {
+    let VerifyArgs {
+        claims,
+        file,
+        language,
+        rules,
+    } = args;
+    let rules = &rules.into();
+    let mut total = claims.len();
+    let mut wrong = 0;
+    // Prints the verdict of a claim, `true` if it holds
+    let check = |mut claim: Claim| {
+        claim.word = language.fold(&claim.word).to_ascii_uppercase();
+        match claim.verify(rules) {
+            Verdict::Confirmed => {
+                println!("{claim} ok");
+                true
+            }
+            Verdict::Mismatch { core, orders } => {
+                let letter = rules
+                    .encoding
+                    .letter(core)
+                    .map(Letter::to_char)
+                    .unwrap_or('?');
+                println!("{claim} mismatch, decodes to {letter} - {core}");
+                for order in orders {
+                    println!("    {order}");
+                }
+                false
+            }
+            Verdict::Failed(e) => {
+                println!("{claim} error, {e}");
+                false
+            }
+        }
+    };
+    for claim in claims {
+        if !check(claim) {
+            wrong += 1;
+        }
+    }
+    for path in file {
+        let text = std::fs::read_to_string(&path).map_err(|e| format!("{path}: {e}"))?;
+        for (number, claim) in parse_claims(&text) {
+            total += 1;
+            match claim {
+                Ok(claim) => {
+                    if !check(claim) {
+                        wrong += 1;
+                    }
+                }
+                Err(e) => {
+                    println!("{path}:{number} {e}");
+                    wrong += 1;
+                }
+            }
+        }
+    }
+    match wrong {
+        0 => Ok(()),
+        _ => Err(format!("{wrong} of {total} claims are wrong")),
+    }
+}
+
+/// Prints every operation of an `expression`, and whether the decryption tries it
+fn evaluate(expression: &Expression, rules: &CoreRules) -> Result<(), String> {
+    println!("{expression}");
+    let evaluation = expression.evaluate(rules);
+    for step in &evaluation.steps {
+        println!("  {step}");
+    }
+    match expression.check_order(rules) {
+        Ok(()) => println!("Operation order tried by the decryption"),
+        Err(e) => println!("Not an operation order of the decryption, {e}"),
+    }
+    let numbers = expression.numbers();
+    if CORE_LENGTHS.contains(&numbers.len()) {
+        match decrypt_numbers_with_arithmetic(&numbers, rules) {
+            Ok(core) => println!("Core of {} : {core}", numbers.iter().format(" ")),
+            Err(e) => println!("Core of {} : {e}", numbers.iter().format(" ")),
+        }
+    }
+    let value = evaluation.value.map_err(|e| format!("Dead branch, {e}"))?;
+    let letter = value
+        .to_integer()
+        .and_then(|value| u32::try_from(value).ok())
+        .and_then(|value| rules.encoding.letter(value));
+    match letter {
+        Some(letter) => println!("Value : {value} - {letter}"),
+        None => println!("Value : {value}"),
+    }
+    Ok(())
+}
+
+/// Prints every completion of the unknown numbers of the `input` that decrypts to the `target`
+fn solve(
+    input: DecryptInput,
+    target: u32,
+    range: RangeInclusive<u32>,
+    rules: &CoreRules,
+    limits: &SearchLimits,
+) -> Result<(), String> {
+    let groups = match input {
+        DecryptInput::Partial(slots) => vec![slots],
+        DecryptInput::Numbers(groups) => groups
+            .into_iter()
+            .map(|numbers| numbers.into_iter().map(Some).collect())
+            .collect(),
+        DecryptInput::Words(_) => {
+            return Err("--target expects numbers, unknown numbers being `?`".to_string());
+        }
+    };
+    let mut found = 0;
+    for slots in groups {
+        let search = solve_numbers(&slots, target, range.clone(), rules, limits);
+        for numbers in &search.numbers {
+            println!("{}", numbers.iter().format(" "));
+        }
+        if let Some(stopped) = search.stopped {
+            eprintln!(
+                "Search stopped, {stopped} after {} results",
+                search.numbers.len()
+            );
+        }
+        found += search.numbers.len();
+    }
+    match found {
+        0 => Err(format!("No numbers in {range:?} decrypt to {target}")),
+        _ => Ok(()),
+    }
+}
+
+/// Prints the error of the `index`-th word, underlined in the `input` if the word is malformed
+fn print_word_error(input: &str, index: usize, error: DecryptError) {
+    if !error.is_input() {
+        println!("{error}");
+        return;
+    }
+    let error = InputError::at(input, index, error);
+    println!("{}", error.diagnostic(input));
+    println!("{error}");
+}
+
+/// Prints every core of a word with unknown letters, with the letters replacing the wildcards
+fn print_wildcard_cores(
+    word: &str,
+    search: &WildcardSearch,
+    readings: Readings,
+    encoding: LetterEncoding,
+) {
+    println!("{word}");
+    for WildcardCore { core, fills } in &search.cores {
+        let fills = fills
+            .iter()
+            .map(|fill| fill.iter().copied().collect::<String>());
+        println!(
+            "  {} - {core}{} : {}",
+            encoding.letter(*core).map(Letter::to_char).unwrap_or('?'),
+            readings_text(*core, readings, encoding),
+            fills.format(", ")
+        );
+    }
+    if let Some(stopped) = search.stopped {
+        let fills = search
+            .cores
+            .iter()
+            .map(|core| core.fills.len())
+            .sum::<usize>();
+        eprintln!("Search stopped, {stopped} after {fills} results");
+    }
+}
+
+/// Alternative readings of a core that is not a letter, like ` (pairs: LZ, modulo: D)`
+fn readings_text(core: u32, readings: Readings, encoding: LetterEncoding) -> String {
+    let readings = interpret(core, readings, encoding);
+    if readings.is_empty() {
+        return String::new();
+    }
+    let readings = readings.into_iter().map(|(reading, letters)| {
+        format!("{reading}: {}", letters.into_iter().collect::<String>())
+    });
+    format!(" ({})", readings.format(", "))
+}
+
+/// Prints the decryption tree, one operation per line
+fn print_trace(trace: &DecryptTrace) {
+    fn print_branches(branches: &[TraceBranch], depth: usize, core: Option<Fraction>) {
+        for branch in branches {
+            let marker = match branch.outcome {
+                Err(_) => " ✗",
+                Ok(value) if branch.next.is_empty() && Some(value) == core => " ✓",
+                Ok(_) => "",
+            };
+            println!("{:indent$}{branch}{marker}", "", indent = depth * 2);
+            print_branches(&branch.next, depth + 1, core);
+        }
+    }
+    println!("  {}", trace.numbers.iter().join(" "));
+    let core = trace.core.as_ref().ok().map(|core| Fraction::from(*core));
+    print_branches(&trace.branches, 2, core);
+}
+
+/// Prints every reachable core, followed by the operation orders producing it
+fn print_candidates(candidates: &[CoreCandidate], encoding: LetterEncoding) {
+    if candidates.len() > 1 {
+        println!("  Ambiguous : {} distinct cores", candidates.len());
+    }
+    for candidate in candidates {
+        let letter = encoding
+            .letter(candidate.core)
+            .map(Letter::to_char)
+            .unwrap_or('?');
+        println!("  {letter} - {}", candidate.core);
+        for order in &candidate.orders {
+            match order.arithmetic() {
+                Arithmetic::Integer => println!("    {order}"),
+                Arithmetic::Rational => println!("    {order} (rational)"),
+            }
+        }
+    }
+}
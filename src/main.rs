@@ -3,8 +3,13 @@ mod cli;
 mod ui;
 
 fn main() -> Result<(), String> {
-    match cli::parse_command_or_exit() {
-        None => ui::run(),
+    let args = cli::parse_args_or_exit();
+    match args.command {
+        None => ui::run(
+            cli::load_dictionary(&args.dict)?,
+            args.encoding,
+            args.language,
+        ),
         Some(command) => cli::run(command),
     }
 }
@@ -1,3 +1,5 @@
+use std::{ops::Range, time::Duration};
+
 use itertools::Itertools;
 use ratatui::{
     crossterm::event::{Event, KeyCode, KeyEventKind},
@@ -8,7 +10,19 @@ use ratatui::{
 use crate::{
     calculator::{
         Letter,
-        decryptor::{DecryptError, DecryptInput, decrypt_numbers, decrypt_word},
+        decryptor::{
+            Core, DecryptError, DecryptInput, InputError, NestedLevel, WildcardCore,
+            WildcardSearch, decrypt_nested, decrypt_numbers_with_arithmetic, decrypt_wildcard_word,
+            decrypt_word_with_arithmetic, has_wildcards, solve_numbers,
+        },
+        encoding::LetterEncoding,
+        expression::{Evaluation, Expression, NotAnOrder},
+        interpret::{Readings, interpret},
+        language::Language,
+        operator::Operation,
+        rules::CoreRules,
+        search::{NumberSearch, SearchLimits, SearchStop},
+        text::{TextToken, TokenDecoding, decrypt_text},
     },
     ui::{App, Mode, widgets::Prompt},
 };
@@ -16,6 +30,12 @@ use crate::{
 /// Sanitize user inputs : do not allow infinite history
 const PREVIOUS_QUERIES_MAX_LEN: usize = 128;
 
+/// Limits of the search of unknown numbers or letters, kept short to keep the interface responsive
+const SEARCH_LIMITS: SearchLimits = SearchLimits {
+    max_results: 100,
+    time_budget: Duration::from_secs(2),
+};
+
 /// Decrypt page state
 #[derive(Default)]
 pub struct Decrypt {
@@ -23,6 +43,18 @@ pub struct Decrypt {
     table_state: TableState,
     selected: Option<usize>,
     prompt: Prompt,
+    /// Alternative readings shown for cores that are not a letter
+    readings: Readings,
+    options: DecryptOptions,
+}
+
+/// Decryption modes toggled from the instructions bar
+#[derive(Debug, Default, Clone, Copy)]
+struct DecryptOptions {
+    /// Decodes the decoded letters again, until it can go no further
+    nested: bool,
+    /// Decodes the 4-letter words of a free-form text
+    text: bool,
 }
 
 impl Decrypt {
@@ -31,7 +63,13 @@ impl Decrypt {
         self.prompt.set_focus(true);
     }
 
-    pub fn draw(&mut self, frame: &mut Frame) {
+    /// Toggles the free-text mode, whose input keeps its case
+    fn toggle_text(&mut self) {
+        self.options.text = !self.options.text;
+        self.prompt.set_keep_case(self.options.text);
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, rules: &CoreRules) {
         let [title_bar, history_area, prompt_area, instructions_bar] = Layout::vertical([
             Constraint::Length(1),
             Constraint::Fill(1),
@@ -48,7 +86,11 @@ impl Decrypt {
             .title(" Previous decryptions ")
             .padding(Padding::horizontal(1));
 
-        let max_input_width = self.history.iter().map(|result| result.input.len()).max();
+        let max_input_width = self
+            .history
+            .iter()
+            .map(|result| result.input.chars().count())
+            .max();
 
         let table = match max_input_width {
             None => Table::new([Row::default()], [Constraint::Fill(1)]),
@@ -60,8 +102,8 @@ impl Decrypt {
                         Default::default()
                     };
                     Row::from_iter([
-                        Text::from(result.input.clone()).italic(),
-                        Text::from(result.output_text()),
+                        Text::from(result.input_text()).italic(),
+                        Text::from(result.output_text(self.readings)),
                     ])
                     .style(style)
                 }),
@@ -87,7 +129,23 @@ impl Decrypt {
             "<4 numbers>".bold().blue(),
             " for core, or ".into(),
             "<Words>".blue().bold(),
-            " for text".into(),
+            " for text, ".into(),
+            "<34 ? 22 4 = S>".blue().bold(),
+            " for unknown numbers, ".into(),
+            "<(34*67-22)/4>".blue().bold(),
+            " to check a formula".into(),
+            " | ".bold(),
+            format!("Arithmetic : {} ", rules.arithmetic).into(),
+            "<F2>".blue().bold(),
+            " | ".bold(),
+            format!("Readings : {} ", self.readings).into(),
+            "<F3>".blue().bold(),
+            " | ".bold(),
+            format!("Nested : {} ", on_off(self.options.nested)).into(),
+            "<F4>".blue().bold(),
+            " | ".bold(),
+            format!("Free text : {} ", on_off(self.options.text)).into(),
+            "<F5>".blue().bold(),
             " | ".bold(),
             "Compute ".into(),
             "<ENTER>".blue().bold(),
@@ -143,57 +201,260 @@ impl Decrypt {
         self.prompt.set_input(&result.input);
     }
 
-    fn input_submitted(&mut self) {
+    fn input_submitted(&mut self, rules: &CoreRules, language: Language) {
         let Some(input) = self.prompt.submit() else {
             return;
         };
+        let input = language.fold(&input);
 
-        let result = process_input(input);
-        if self.history.len() > PREVIOUS_QUERIES_MAX_LEN {
-            self.history.remove(0);
+        for result in process_input(input, rules, &self.options) {
+            if self.history.len() > PREVIOUS_QUERIES_MAX_LEN {
+                self.history.remove(0);
+            }
+            self.history.push(result);
         }
-        self.history.push(result);
         self.table_state.select_last();
         self.selected = None;
     }
 }
 
-fn process_input(input: String) -> DecryptResult {
-    let mut result: DecryptResult = DecryptResult::new(input);
+fn on_off(enabled: bool) -> &'static str {
+    match enabled {
+        true => "on",
+        false => "off",
+    }
+}
+
+/// Decrypts a user's input, with one result for each group of numbers
+fn process_input(input: String, rules: &CoreRules, options: &DecryptOptions) -> Vec<DecryptResult> {
+    let mut result: DecryptResult = DecryptResult::new(input, rules.encoding);
+
+    if options.text {
+        result.tokens = decrypt_text(&result.input, rules);
+        let cores = result
+            .tokens
+            .iter()
+            .filter_map(|token| match &token.decoding {
+                TokenDecoding::Core(core) => Some(core.clone()),
+                TokenDecoding::Skipped => None,
+            })
+            .collect::<Vec<_>>();
+        for core in cores {
+            result.push_result(core.map(Core::from));
+        }
+        return vec![result];
+    }
+
+    if is_expression(&result.input) {
+        match result.input.parse::<Expression>() {
+            Ok(expression) => {
+                result.expression = Some(ExpressionResult {
+                    evaluation: expression.evaluate(rules),
+                    order: expression.check_order(rules),
+                })
+            }
+            Err(e) => result.push_error(e.to_string()),
+        }
+        return vec![result];
+    }
+
+    if let Some((query, target)) = result.input.split_once('=') {
+        match (query.parse(), parse_target(target, rules.encoding)) {
+            (Err(e), _) => {
+                result.faulty.push(e.span.clone());
+                result.push_error(e.to_string());
+            }
+            (Ok(_), None) => result.push_error(format!("Invalid target `{}`", target.trim())),
+            (Ok(DecryptInput::Partial(slots)), Some(target)) => {
+                let domain = rules.encoding.numbers();
+                result.solutions =
+                    Some(solve_numbers(&slots, target, domain, rules, &SEARCH_LIMITS));
+            }
+            (Ok(DecryptInput::Numbers(groups)), Some(target)) if groups.len() == 1 => {
+                let slots = groups[0].iter().copied().map(Some).collect::<Vec<_>>();
+                let domain = rules.encoding.numbers();
+                result.solutions =
+                    Some(solve_numbers(&slots, target, domain, rules, &SEARCH_LIMITS));
+            }
+            (Ok(_), Some(_)) => result.push_error(
+                "Expected a single group of numbers, `?` for unknown numbers".to_string(),
+            ),
+        }
+        return vec![result];
+    }
 
     match result.input.parse() {
-        Err(e) => result.push_error(e.to_string()),
-        Ok(DecryptInput::Numbers(numbers)) => {
-            result.push_result(decrypt_numbers(numbers));
+        Err(e) => {
+            result.faulty.push(e.span.clone());
+            result.push_error(e.to_string());
+        }
+        Ok(DecryptInput::Partial(_)) => {
+            result.push_error("Unknown numbers `?` need a target : `34 ? 22 4 = S`".to_string())
+        }
+        Ok(DecryptInput::Numbers(groups)) if groups.len() > 1 => {
+            return DecryptInput::number_groups(&result.input)
+                .zip(groups)
+                .map(|(group, numbers)| {
+                    let mut result = DecryptResult::new(group.to_string(), rules.encoding);
+                    result.push_result(decrypt_numbers_with_arithmetic(&numbers, rules));
+                    result
+                })
+                .collect();
+        }
+        Ok(DecryptInput::Numbers(groups)) => {
+            for numbers in groups {
+                result.push_result(decrypt_numbers_with_arithmetic(&numbers, rules));
+            }
         }
         Ok(DecryptInput::Words(words)) => {
-            for word in words {
-                result.push_result(decrypt_word(&word));
+            if options.nested {
+                result.nested = decrypt_nested(&words, rules).split_off(1);
+            }
+            for (index, word) in words.into_iter().enumerate() {
+                match has_wildcards(&word) {
+                    true => match decrypt_wildcard_word(&word, rules, &SEARCH_LIMITS) {
+                        Ok(search) => result.push_wildcard(word, search),
+                        Err(error) => result.push_word_error(index, error),
+                    },
+                    false => match decrypt_word_with_arithmetic(&word, rules) {
+                        Ok(core) => result.push_core(core),
+                        Err(error) => result.push_word_error(index, error),
+                    },
+                }
             }
         }
     }
 
-    result
+    vec![result]
+}
+
+/// Whether the input holds an operator between two numbers, like `34 - 4` or `(34-4)*22`, never found in words or groups of numbers
+fn is_expression(input: &str) -> bool {
+    input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .tuple_windows()
+        .any(|(lhs, op, rhs)| {
+            (lhs.is_ascii_digit() || lhs == ')')
+                && Operation::from_symbol(op).is_some()
+                && (rhs.is_ascii_digit() || rhs == '(')
+        })
+}
+
+/// Target core of a search, written as a number or as a letter
+fn parse_target(target: &str, encoding: LetterEncoding) -> Option<u32> {
+    let target = target.trim();
+    if let Ok(core) = target.parse() {
+        return Some(core);
+    }
+    let mut chars = target.chars();
+    match (chars.next().and_then(Letter::try_from_char), chars.next()) {
+        (Some(letter), None) => Some(encoding.number(letter)),
+        _ => None,
+    }
 }
 
 /// Record of a user's text input and it's decryption
 #[derive(Debug, PartialEq, Eq)]
 struct DecryptResult {
     input: String,
-    cores: Vec<Option<u32>>,
+    cores: Vec<Option<Core>>,
     errors: Vec<String>,
+    /// Words with unknown letters, their core is `None`
+    wildcards: Vec<WildcardResult>,
+    /// Levels of a nested decryption, after the first one
+    nested: Vec<NestedLevel>,
+    /// Every word of a free-form text, including skipped words
+    tokens: Vec<TextToken>,
+    /// Byte spans of the malformed words of the input, drawn in red
+    faulty: Vec<Range<usize>>,
+    /// Encoding used to read the cores as letters
+    encoding: LetterEncoding,
+    /// Completions of the unknown numbers reaching a target core
+    solutions: Option<NumberSearch>,
+    /// Value of an arithmetic expression
+    expression: Option<ExpressionResult>,
+}
+
+/// Value of an arithmetic expression, and whether the decryption tries it
+#[derive(Debug, PartialEq, Eq)]
+struct ExpressionResult {
+    evaluation: Evaluation,
+    order: Result<(), NotAnOrder>,
+}
+
+/// Possible cores of a word with unknown letters
+#[derive(Debug, PartialEq, Eq)]
+struct WildcardResult {
+    /// Position of the word in the input
+    position: usize,
+    word: String,
+    cores: Vec<WildcardCore>,
+    /// `None` if every word was tried
+    stopped: Option<SearchStop>,
 }
+
 impl DecryptResult {
-    fn new(input: String) -> Self {
+    fn new(input: String, encoding: LetterEncoding) -> Self {
         DecryptResult {
             input,
             cores: Vec::new(),
             errors: Vec::new(),
+            wildcards: Vec::new(),
+            nested: Vec::new(),
+            tokens: Vec::new(),
+            faulty: Vec::new(),
+            encoding,
+            solutions: None,
+            expression: None,
+        }
+    }
+
+    /// store the error of the `index`-th word, marking the word if it is malformed
+    fn push_word_error(&mut self, index: usize, error: DecryptError) {
+        let message = match error.is_input() {
+            true => {
+                let error = InputError::at(&self.input, index, error);
+                self.faulty.push(error.span.clone());
+                error.to_string()
+            }
+            false => error.to_string(),
+        };
+        self.push_error(message);
+    }
+
+    /// Input with the malformed words in red
+    fn input_text(&self) -> Line<'_> {
+        let mut spans = Vec::new();
+        let mut copied = 0;
+        for span in &self.faulty {
+            spans.push(Span::from(&self.input[copied..span.start]));
+            spans.push(Span::from(&self.input[span.clone()]).red());
+            copied = span.end;
         }
+        spans.push(Span::from(&self.input[copied..]));
+        Line::from(spans)
+    }
+
+    /// store the possible cores of a word with unknown letters
+    fn push_wildcard(&mut self, word: String, search: WildcardSearch) {
+        self.wildcards.push(WildcardResult {
+            position: self.cores.len(),
+            word,
+            cores: search.cores,
+            stopped: search.stopped,
+        });
+        self.cores.push(None);
+    }
+
+    fn wildcard(&self, position: usize) -> Option<&WildcardResult> {
+        self.wildcards
+            .iter()
+            .find(|wildcard| wildcard.position == position)
     }
 
     /// store a successful core
-    fn push_core(&mut self, core: u32) {
+    fn push_core(&mut self, core: Core) {
         self.cores.push(Some(core));
     }
 
@@ -204,14 +465,58 @@ impl DecryptResult {
     }
 
     /// automatically determine whether to store a core or an error
-    fn push_result(&mut self, result: Result<u32, DecryptError>) {
+    fn push_result(&mut self, result: Result<Core, DecryptError>) {
         match result {
             Ok(core) => self.push_core(core),
             Err(error) => self.push_error(error.to_string()),
         }
     }
 
-    fn output_text(&self) -> Line<'_> {
+    fn output_text(&self, readings: Readings) -> Line<'_> {
+        if let Some(expression) = &self.expression {
+            let steps = expression
+                .evaluation
+                .steps
+                .iter()
+                .map(|step| Span::from(step.to_string()));
+            let steps = Itertools::intersperse(steps, Span::from(", "));
+            let value = match expression.evaluation.value {
+                Ok(value) => {
+                    let letter = value
+                        .to_integer()
+                        .and_then(|value| u32::try_from(value).ok())
+                        .and_then(|value| self.encoding.letter(value));
+                    match letter {
+                        Some(letter) => Span::from(format!(". Value : {value} - {letter}")),
+                        None => Span::from(format!(". Value : {value}")),
+                    }
+                    .green()
+                }
+                Err(e) => Span::from(format!(". Dead branch : {e}")).red(),
+            };
+            let order = match expression.order {
+                Ok(()) => Span::from(". Order tried by the decryption").green(),
+                Err(e) => Span::from(format!(". Not a decryption order : {e}")).yellow(),
+            };
+            return Line::from_iter(steps.chain([value, order]));
+        }
+        if let Some(search) = &self.solutions {
+            let stopped = match search.stopped {
+                Some(stopped) => Span::from(format!(". Search stopped, {stopped}")).yellow(),
+                None => Span::default(),
+            };
+            let solutions = match search.numbers.is_empty() {
+                true => Span::from("No solution").red(),
+                false => {
+                    let numbers = search
+                        .numbers
+                        .iter()
+                        .map(|numbers| numbers.iter().format(" "));
+                    Span::from(format!("Solutions : {}", numbers.format(", "))).green()
+                }
+            };
+            return Line::from_iter([solutions, stopped]);
+        }
         let errors_header = match self.errors.len() {
             0 => Span::default(),
             1 => Span::from("Error : "),
@@ -231,9 +536,14 @@ impl DecryptResult {
             _ => Span::from("Values : "),
         }
         .green();
-        let values = self.cores.iter().map(|core| match core {
-            Some(number) => Span::from(number.to_string()).green(),
-            None => Span::from("?").red(),
+        let values = self.cores.iter().enumerate().map(|(position, core)| {
+            match (core, self.wildcard(position)) {
+                (Some(number), _) => Span::from(number.to_string()).green(),
+                (None, Some(wildcard)) => {
+                    Span::from(format!("{} cores", wildcard.cores.len())).yellow()
+                }
+                (None, None) => Span::from("?").red(),
+            }
         });
         let values = Itertools::intersperse(values, Span::from(", ").green());
 
@@ -242,19 +552,83 @@ impl DecryptResult {
             _ => Span::from(". Text : "),
         }
         .green();
-        let text = self
-            .cores
-            .iter()
-            .map(|core| match core.and_then(Letter::try_from_num) {
+        let text = self.cores.iter().enumerate().map(|(position, core)| {
+            match core.and_then(|core| self.encoding.letter(core.value)) {
                 Some(letter) => Span::from(letter.to_string()).green(),
                 None => {
                     let span = Span::from("?");
-                    match core.is_some() {
-                        true => span.green(),
-                        false => span.red(),
+                    match (core.is_some(), self.wildcard(position).is_some()) {
+                        (true, _) => span.green(),
+                        (false, true) => span.yellow(),
+                        (false, false) => span.red(),
                     }
                 }
+            }
+        });
+
+        // Cores of words with unknown letters, with the letters producing each one : `PE?K : A:A B:B E:E,Y`
+        let wildcards = self.wildcards.iter().map(|wildcard| {
+            let cores = wildcard.cores.iter().map(|core| {
+                let label = match self.encoding.letter(core.core) {
+                    Some(letter) => letter.to_string(),
+                    None => core.core.to_string(),
+                };
+                let fills = core
+                    .fills
+                    .iter()
+                    .map(|fill| fill.iter().copied().collect::<String>());
+                format!("{label}:{}", fills.format(","))
             });
+            let stopped = match wildcard.stopped {
+                Some(stopped) => format!(". Search stopped, {stopped}"),
+                None => String::new(),
+            };
+            Span::from(format!(
+                ". {} : {}{stopped}",
+                wildcard.word,
+                cores.format(" ")
+            ))
+            .yellow()
+        });
+
+        // Words of a free-form text, with their offset and decoding : `PIGS@5 S`
+        let tokens = match self.tokens.is_empty() {
+            true => Span::default(),
+            false => {
+                let tokens = self.tokens.iter().map(|token| {
+                    let decoding = token.decoding.text(self.encoding);
+                    format!("{}@{} {decoding}", token.word, token.offset)
+                });
+                Span::from(format!(". Words : {}", tokens.format(", "))).dark_gray()
+            }
+        };
+
+        let nested = match self.nested.is_empty() {
+            true => Span::default(),
+            false => {
+                let levels = self.nested.iter().map(|level| level.text(self.encoding));
+                Span::from(format!(". Nested : {}", levels.format(" → "))).cyan()
+            }
+        };
+
+        let readings = self
+            .cores
+            .iter()
+            .flatten()
+            .map(|core| (core.value, interpret(core.value, readings, self.encoding)))
+            .filter(|(_, readings)| !readings.is_empty())
+            .map(|(core, readings)| {
+                let readings = readings.into_iter().map(|(reading, letters)| {
+                    format!("{reading} {}", letters.into_iter().collect::<String>())
+                });
+                Span::from(format!("{core} {}", readings.format(", "))).yellow()
+            })
+            .collect_vec();
+        let readings_header = match readings.is_empty() {
+            true => Span::default(),
+            false => Span::from(". Readings : ").yellow(),
+        };
+        let readings = Itertools::intersperse(readings.into_iter(), Span::from(" ; ").yellow());
 
         let text_footer = match self.errors.len() {
             0 => Span::default(),
@@ -266,6 +640,11 @@ impl DecryptResult {
                 .chain(values)
                 .chain([text_header])
                 .chain(text)
+                .chain(wildcards)
+                .chain([nested])
+                .chain([tokens])
+                .chain([readings_header])
+                .chain(readings)
                 .chain([text_footer])
                 .chain([errors_header])
                 .chain(errors),
@@ -286,7 +665,11 @@ pub fn handle_events(app: &mut App, event: Event) {
             KeyCode::Down => app.decrypt.history_down(),
             KeyCode::Home => app.decrypt.prompt.cursor_start(),
             KeyCode::End => app.decrypt.prompt.cursor_end(),
-            KeyCode::Enter => app.decrypt.input_submitted(),
+            KeyCode::Enter => app.decrypt.input_submitted(&app.rules, app.language),
+            KeyCode::F(2) => app.rules.arithmetic = app.rules.arithmetic.toggle(),
+            KeyCode::F(3) => app.decrypt.readings = app.decrypt.readings.cycle(),
+            KeyCode::F(4) => app.decrypt.options.nested = !app.decrypt.options.nested,
+            KeyCode::F(5) => app.decrypt.toggle_text(),
             _ => (),
         },
         _ => (),
@@ -300,24 +683,199 @@ mod tests {
     #[test]
     fn known_words() {
         let input = "PIGS SAND\r\nMAIL DATE\tHEAD".to_string();
-        let result = process_input(input.clone());
+        let result = process_input(
+            input.clone(),
+            &CoreRules::default(),
+            &DecryptOptions::default(),
+        );
         let expected = DecryptResult {
             input,
-            cores: vec![Some(19), Some(20), Some(9), Some(12), Some(12)],
+            cores: [19, 20, 9, 12, 12].map(|core| Some(core.into())).into(),
             errors: Vec::new(),
+            wildcards: Vec::new(),
+            nested: Vec::new(),
+            tokens: Vec::new(),
+            faulty: Vec::new(),
+            encoding: LetterEncoding::A1Z26,
+            solutions: None,
+            expression: None,
         };
-        assert_eq!(result, expected);
+        assert_eq!(result, [expected]);
     }
 
     #[test]
     fn known_numbers() {
         let input = "1000 200 11 2".to_string();
-        let result = process_input(input.clone());
+        let result = process_input(
+            input.clone(),
+            &CoreRules::default(),
+            &DecryptOptions::default(),
+        );
         let expected = DecryptResult {
             input,
-            cores: vec![Some(53)],
+            cores: vec![Some(53.into())],
             errors: Vec::new(),
+            wildcards: Vec::new(),
+            nested: Vec::new(),
+            tokens: Vec::new(),
+            faulty: Vec::new(),
+            encoding: LetterEncoding::A1Z26,
+            solutions: None,
+            expression: None,
+        };
+        assert_eq!(result, [expected]);
+
+        let result = process_input(
+            "1000 200 11 2 | 5 6 7 8\n34 67 22 4".to_string(),
+            &CoreRules::default(),
+            &DecryptOptions::default(),
+        );
+        let inputs = result.iter().map(|result| result.input.as_str());
+        assert_eq!(
+            inputs.collect::<Vec<_>>(),
+            ["1000 200 11 2", "5 6 7 8", "34 67 22 4"]
+        );
+        assert_eq!(result[0].cores, [Some(53.into())]);
+    }
+
+    #[test]
+    fn nested_words() {
+        let input = "SAND WELL JOYA JOYA".to_string();
+        let options = DecryptOptions {
+            nested: true,
+            ..Default::default()
+        };
+        let result = process_input(input, &CoreRules::default(), &options).remove(0);
+        assert_eq!(result.nested.len(), 1);
+        assert_eq!(result.nested[0].words, ["TREE"]);
+        let line = result.output_text(Readings::empty());
+        let text = line.spans.iter().map(|span| span.content.clone()).join("");
+        assert!(text.ends_with("Text : TREE. Nested : B"), "{text}");
+    }
+
+    #[test]
+    fn free_text() {
+        let options = DecryptOptions {
+            text: true,
+            ..Default::default()
+        };
+        let result = process_input(
+            "Dear PIGS, the SAND!".into(),
+            &CoreRules::default(),
+            &options,
+        )
+        .remove(0);
+        assert_eq!(result.cores, [2, 19, 20].map(|core| Some(core.into())));
+        assert_eq!(result.tokens.len(), 4);
+        let line = result.output_text(Readings::empty());
+        let text = line.spans.iter().map(|span| span.content.clone()).join("");
+        assert!(
+            text.ends_with("Words : Dear@0 B, PIGS@5 S, the@11 skipped, SAND@15 T"),
+            "{text}"
+        );
+
+        let mut decrypt = Decrypt::default();
+        decrypt.toggle_text();
+        "Dear PIGS"
+            .chars()
+            .for_each(|c| decrypt.prompt.input_char(c));
+        let input = decrypt.prompt.submit().unwrap();
+        let result = process_input(input, &CoreRules::default(), &decrypt.options).remove(0);
+        assert_eq!(result.input, "Dear PIGS");
+        assert_eq!(result.cores, [2, 19].map(|core| Some(core.into())));
+    }
+
+    #[test]
+    fn unknown_numbers() {
+        let rules = CoreRules::default();
+        let options = DecryptOptions::default();
+        let result = process_input("34 ? 22 4 = C".to_string(), &rules, &options).remove(0);
+        let search = result.solutions.as_ref().unwrap();
+        assert_eq!(search.numbers, [vec![34, 1, 22, 4]]);
+        let line = result.output_text(Readings::empty());
+        assert_eq!(line.spans[0].content, "Solutions : 34 1 22 4");
+
+        let result = process_input("34 ? 22 4".to_string(), &rules, &options).remove(0);
+        assert_eq!(result.solutions, None);
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[test]
+    fn expressions() {
+        let rules = CoreRules::default();
+        let options = DecryptOptions::default();
+        let result = process_input("(34*67-22)/4".to_string(), &rules, &options).remove(0);
+        let expression = result.expression.as_ref().unwrap();
+        assert_eq!(expression.evaluation.value, Ok(564.into()));
+        assert_eq!(expression.order, Ok(()));
+        let line = result.output_text(Readings::empty());
+        let text = line.spans.iter().map(|span| span.content.clone()).join("");
+        assert!(
+            text.ends_with("2256 / 4 = 564. Value : 564. Order tried by the decryption"),
+            "{text}"
+        );
+
+        let result = process_input("(34 - 4".to_string(), &rules, &options).remove(0);
+        assert_eq!(result.errors, ["Invalid expression, unexpected end"]);
+
+        let result = process_input("SAND WELL-MADE".to_string(), &rules, &options).remove(0);
+        assert_eq!(result.expression, None);
+        assert_eq!(result.cores[0], Some(20.into()));
+        assert_eq!(result.faulty, vec![Range { start: 5, end: 14 }]);
+        assert!(!is_expression("TICK~TOCK & 4") && !is_expression("34 67 | 22 4"));
+    }
+
+    #[test]
+    fn faulty_words() {
+        let result = process_input(
+            "HEAT TICKETS DATE".to_string(),
+            &CoreRules::default(),
+            &DecryptOptions::default(),
+        )
+        .remove(0);
+        assert_eq!(result.faulty, vec![Range { start: 5, end: 12 }]);
+        let line = result.input_text();
+        assert_eq!(line.spans[1].content, "TICKETS");
+        assert_eq!(line.spans[1].style.fg, Some(Color::Red));
+        assert_eq!(
+            result.errors,
+            ["Invalid length, expected 3 to 6 character words (word 2)"]
+        );
+    }
+
+    #[test]
+    fn wildcard_words() {
+        let result = process_input(
+            "TREE PE?K".to_string(),
+            &CoreRules::default(),
+            &DecryptOptions::default(),
+        )
+        .remove(0);
+        assert_eq!(result.cores, vec![Some(2.into()), None]);
+        assert!(result.errors.is_empty());
+        assert_eq!(result.wildcards.len(), 1);
+        assert_eq!(result.wildcards[0].position, 1);
+        let line = result.output_text(Readings::empty());
+        let text = line.spans.iter().map(|span| span.content.clone()).join("");
+        assert!(text.contains(". PE?K : A:A B:B"), "{text}");
+    }
+
+    #[test]
+    fn readings() {
+        let result = process_input(
+            "1226 1 1 1".to_string(),
+            &CoreRules::default(),
+            &DecryptOptions::default(),
+        )
+        .remove(0);
+        let text = |readings| {
+            let line = result.output_text(readings);
+            line.spans.iter().map(|span| span.content.clone()).join("")
         };
-        assert_eq!(result, expected)
+        assert_eq!(text(Readings::empty()), "Value : 1225. Text : ?");
+        assert_eq!(
+            text(Readings::all()),
+            "Value : 1225. Text : ?. Readings : 1225 modulo C, pairs LY"
+        );
     }
 }
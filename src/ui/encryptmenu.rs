@@ -1,5 +1,15 @@
+use std::time::Duration;
+
 use crate::{
-    calculator::{CORE_LENGTH, Letter, encryptor::encrypt_letter},
+    calculator::{
+        CORE_LENGTH, CORE_LENGTHS, Letter,
+        dictionary::{Dictionary, SortOrder},
+        encryptor::encrypt_letter,
+        language::Language,
+        pattern::Pattern,
+        rules::CoreRules,
+        search::{SearchLimits, SearchStop},
+    },
     ui::{App, Mode, widgets::Prompt},
 };
 use ratatui::{
@@ -8,17 +18,86 @@ use ratatui::{
     widgets::{Block, Padding, Row, Table},
 };
 
+/// Limits of the search of words, kept short to keep the interface responsive
+const SEARCH_LIMITS: SearchLimits = SearchLimits {
+    max_results: 10_000,
+    time_budget: Duration::from_secs(2),
+};
+
 #[derive(Default)]
 pub struct Encrypt {
     results: Option<EncryptResults>,
     page_start: usize,
     page_len: u16,
     prompt: Prompt,
+    dictionary_mode: DictionaryMode,
+    sort: SortOrder,
 }
 
 struct EncryptResults {
     input: String,
-    cyphers: Vec<[Letter; CORE_LENGTH]>,
+    length: usize,
+    cyphers: Vec<Vec<Letter>>,
+    /// Whether each cypher is a dictionary word
+    hits: Vec<bool>,
+    /// `None` if every word was tried
+    stopped: Option<SearchStop>,
+}
+impl EncryptResults {
+    fn new(
+        input: String,
+        length: usize,
+        mut cyphers: Vec<Vec<Letter>>,
+        stopped: Option<SearchStop>,
+        sort: SortOrder,
+        dictionary: Option<&Dictionary>,
+    ) -> Self {
+        sort.sort(&mut cyphers, dictionary);
+        let hits = cyphers
+            .iter()
+            .map(|cypher| dictionary.is_some_and(|dictionary| dictionary.contains(cypher)))
+            .collect();
+        Self {
+            input,
+            length,
+            cyphers,
+            hits,
+            stopped,
+        }
+    }
+
+    /// Cyphers to display, and whether they are dictionary words
+    fn visible(&self, mode: DictionaryMode) -> Vec<(&[Letter], bool)> {
+        self.cyphers
+            .iter()
+            .zip(&self.hits)
+            .filter(|(_, hit)| mode != DictionaryMode::HitsOnly || **hit)
+            .map(|(cypher, hit)| (cypher.as_slice(), mode != DictionaryMode::Off && *hit))
+            .collect()
+    }
+}
+
+/// How dictionary words are shown in the results
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+enum DictionaryMode {
+    #[default]
+    #[display("off")]
+    Off,
+    /// Dictionary words are highlighted
+    #[display("mark")]
+    Mark,
+    /// Only dictionary words are shown
+    #[display("hits only")]
+    HitsOnly,
+}
+impl DictionaryMode {
+    fn cycle(self) -> Self {
+        match self {
+            Self::Off => Self::Mark,
+            Self::Mark => Self::HitsOnly,
+            Self::HitsOnly => Self::Off,
+        }
+    }
 }
 
 impl Encrypt {
@@ -27,7 +106,9 @@ impl Encrypt {
         self.prompt.set_focus(true);
     }
 
-    pub fn draw(&mut self, frame: &mut Frame) {
+    pub fn draw(&mut self, frame: &mut Frame, rules: &CoreRules, dictionary: Option<&Dictionary>) {
+        let has_dictionary = dictionary.is_some();
+        let has_counts = dictionary.is_some_and(Dictionary::has_counts);
         let [title_bar, results_area, prompt_area, instructions_bar] = Layout::vertical([
             Constraint::Length(1),
             Constraint::Fill(1),
@@ -45,6 +126,10 @@ impl Encrypt {
                 " Possible encryptions for : '".into(),
                 results.input.clone().blue(),
                 "' ".into(),
+                match results.stopped {
+                    Some(stopped) => format!("Search stopped, {stopped} ").yellow(),
+                    None => "".into(),
+                },
             ]),
             None => " Enter a letter to compute ".into(),
         };
@@ -52,13 +137,14 @@ impl Encrypt {
             .title(results_title)
             .padding(Padding::horizontal(1));
 
-        let table = match &mut self.results {
+        let table = match &self.results {
             None => Table::default(),
             Some(results) => {
-                let col_width = CORE_LENGTH as u16 + 2;
+                let cyphers = results.visible(self.dictionary_mode);
+                let col_width = results.length as u16 + 2;
                 let table_rows = u16::max(1, results_area.height.saturating_sub(2));
                 let table_cols = u16::max(1, results_area.width.saturating_sub(2) / col_width);
-                let total_cols = 1 + (results.cyphers.len() / table_rows as usize);
+                let total_cols = 1 + (cyphers.len() / table_rows as usize);
                 let total_pages = 1 + (total_cols / table_cols as usize);
                 self.page_len = table_rows * table_cols;
                 let current_page = self.page_start / self.page_len as usize + 1;
@@ -70,14 +156,19 @@ impl Encrypt {
                 Table::new(
                     (0..table_rows).map(|row| {
                         Row::new((0..table_cols).filter_map(|col| {
-                            results
-                                .cyphers
+                            cyphers
                                 .get(
                                     self.page_start
                                         + row as usize
                                         + (col as usize * table_rows as usize),
                                 )
-                                .map(|letters| letters.iter().cloned().collect::<String>())
+                                .map(|(letters, hit)| {
+                                    let text = letters.iter().cloned().collect::<String>();
+                                    match hit {
+                                        true => text.green().bold(),
+                                        false => text.into(),
+                                    }
+                                })
                         }))
                     }),
                     std::iter::repeat_n(Constraint::Length(col_width), table_cols as usize),
@@ -92,6 +183,25 @@ impl Encrypt {
         Line::from_iter([
             " Input : ".into(),
             "<Letter>".blue().bold(),
+            " and optional ".into(),
+            "<Length>".blue().bold(),
+            " or ".into(),
+            "<Pattern>".blue().bold(),
+            " | ".bold(),
+            format!("Arithmetic : {} ", rules.arithmetic).into(),
+            "<F2>".blue().bold(),
+            " | ".bold(),
+            match has_dictionary {
+                true => format!("Dictionary : {} ", self.dictionary_mode).into(),
+                false => "No dictionary ".dark_gray(),
+            },
+            "<F3>".blue().bold(),
+            " | ".bold(),
+            match has_counts {
+                true => format!("Sort : {} ", self.sort).into(),
+                false => "No word counts ".dark_gray(),
+            },
+            "<F4>".blue().bold(),
             " | ".bold(),
             "Compute ".into(),
             "<ENTER>".blue().bold(),
@@ -106,14 +216,51 @@ impl Encrypt {
         .render(instructions_bar, frame.buffer_mut());
     }
 
-    fn input_submitted(&mut self) {
+    fn input_submitted(
+        &mut self,
+        rules: &CoreRules,
+        language: Language,
+        dictionary: Option<&Dictionary>,
+    ) {
         let Some(input) = self.prompt.submit() else {
             return;
         };
-        let Ok(cyphers) = input.parse().map(encrypt_letter) else {
+        let input = language.fold(&input);
+        let Some((letter, pattern)) = parse_query(&input) else {
+            return;
+        };
+        let length = pattern.len();
+        let Ok(search) = encrypt_letter(letter, &pattern, rules, &SEARCH_LIMITS) else {
             return;
         };
-        self.results = Some(EncryptResults { input, cyphers });
+        self.results = Some(EncryptResults::new(
+            input,
+            length,
+            search.words,
+            search.stopped,
+            self.sort,
+            dictionary,
+        ));
+        self.page_start = 0;
+    }
+
+    fn toggle_sort(&mut self, dictionary: Option<&Dictionary>) {
+        if !dictionary.is_some_and(Dictionary::has_counts) {
+            return;
+        }
+        self.sort = self.sort.toggle();
+        if let Some(results) = self.results.take() {
+            let EncryptResults {
+                input,
+                length,
+                cyphers,
+                stopped,
+                ..
+            } = results;
+            self.results = Some(EncryptResults::new(
+                input, length, cyphers, stopped, self.sort, dictionary,
+            ));
+        }
         self.page_start = 0;
     }
 
@@ -125,12 +272,27 @@ impl Encrypt {
         let Some(results) = &mut self.results else {
             return;
         };
-        let first_of_last_page =
-            results.cyphers.len() - results.cyphers.len() % self.page_len as usize;
+        let len = results.visible(self.dictionary_mode).len();
+        let first_of_last_page = len - len % self.page_len as usize;
         self.page_start = usize::min(self.page_start + self.page_len as usize, first_of_last_page);
     }
 }
 
+/// Parses a letter, optionally followed by the length of the words or a pattern like `T??E`
+fn parse_query(input: &str) -> Option<(Letter, Pattern)> {
+    let mut parts = input.split_whitespace();
+    let letter = parts.next()?.parse().ok()?;
+    let pattern = match parts.next() {
+        None => Pattern::any(CORE_LENGTH),
+        Some(field) => match field.parse::<usize>() {
+            Ok(length) if CORE_LENGTHS.contains(&length) => Pattern::any(length),
+            Ok(_) => return None,
+            Err(_) => field.parse().ok()?,
+        },
+    };
+    parts.next().is_none().then_some((letter, pattern))
+}
+
 pub fn handle_events(app: &mut App, event: Event) {
     match event {
         Event::Key(key_event) if key_event.kind == KeyEventKind::Press => match key_event.code {
@@ -142,7 +304,16 @@ pub fn handle_events(app: &mut App, event: Event) {
             KeyCode::Right => app.encrypt.prompt.cursor_right(),
             KeyCode::Home => app.encrypt.prompt.cursor_start(),
             KeyCode::End => app.encrypt.prompt.cursor_end(),
-            KeyCode::Enter => app.encrypt.input_submitted(),
+            KeyCode::Enter => {
+                app.encrypt
+                    .input_submitted(&app.rules, app.language, app.dictionary.as_ref())
+            }
+            KeyCode::F(3) if app.dictionary.is_some() => {
+                app.encrypt.dictionary_mode = app.encrypt.dictionary_mode.cycle();
+                app.encrypt.page_start = 0;
+            }
+            KeyCode::F(2) => app.rules.arithmetic = app.rules.arithmetic.toggle(),
+            KeyCode::F(4) => app.encrypt.toggle_sort(app.dictionary.as_ref()),
             KeyCode::PageUp => app.encrypt.previous_page(),
             KeyCode::PageDown => app.encrypt.next_page(),
             _ => (),
@@ -6,10 +6,29 @@ mod widgets;
 use crossterm::event::Event;
 use ratatui::{DefaultTerminal, Frame, crossterm::event};
 
-use crate::ui::{decryptmenu::Decrypt, encryptmenu::Encrypt, mainmenu::MainMenu};
+use crate::{
+    calculator::{
+        dictionary::Dictionary, encoding::LetterEncoding, language::Language, rules::CoreRules,
+    },
+    ui::{decryptmenu::Decrypt, encryptmenu::Encrypt, mainmenu::MainMenu},
+};
 
-pub fn run() -> Result<(), String> {
-    ratatui::run(|terminal| App::default().run(terminal)).map_err(|io_error| io_error.to_string())
+/// Runs the Terminal UI. Without a `dictionary`, the embedded english list is used if available
+pub fn run(
+    dictionary: Option<Dictionary>,
+    encoding: LetterEncoding,
+    language: Language,
+) -> Result<(), String> {
+    let mut app = App {
+        language,
+        rules: CoreRules {
+            encoding,
+            ..Default::default()
+        },
+        dictionary: dictionary.or_else(Dictionary::english),
+        ..Default::default()
+    };
+    ratatui::run(|terminal| app.run(terminal)).map_err(|io_error| io_error.to_string())
 }
 
 /// Application state
@@ -21,6 +40,12 @@ struct App {
     encrypt: Encrypt,
     /// Current active page
     mode: Mode,
+    /// Rules used by every computation
+    rules: CoreRules,
+    /// Real words, highlighted in encryption results
+    dictionary: Option<Dictionary>,
+    /// Language of the inputs, their accented letters are folded
+    language: Language,
 }
 
 /// Current page being displayed
@@ -48,8 +73,10 @@ impl App {
             Mode::MainMenu => {
                 self.main_menu.draw(frame);
             }
-            Mode::Decrypt => self.decrypt.draw(frame),
-            Mode::Encrypt => self.encrypt.draw(frame),
+            Mode::Decrypt => self.decrypt.draw(frame, &self.rules),
+            Mode::Encrypt => self
+                .encrypt
+                .draw(frame, &self.rules, self.dictionary.as_ref()),
             Mode::Quit => (),
         }
     }
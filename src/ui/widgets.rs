@@ -16,8 +16,11 @@ use ratatui::{
 #[derive(Default)]
 pub struct Prompt {
     input: String,
+    /// Position of the cursor, counted in characters
     cursor_index: usize,
     has_focus: bool,
+    /// Keeps the case of typed letters, instead of uppercasing them
+    keep_case: bool,
 }
 
 impl Prompt {
@@ -45,7 +48,20 @@ impl Prompt {
 
     pub fn set_input(&mut self, input: &str) {
         self.input = input.to_string();
-        self.cursor_index = input.len();
+        self.cursor_index = input.chars().count();
+    }
+
+    /// Number of characters of the input
+    fn len(&self) -> usize {
+        self.input.chars().count()
+    }
+
+    /// Position of the cursor in the input, counted in bytes
+    fn byte_index(&self) -> usize {
+        self.input
+            .char_indices()
+            .nth(self.cursor_index)
+            .map_or(self.input.len(), |(index, _)| index)
     }
 
     pub fn set_focus(&mut self, bool: bool) {
@@ -57,18 +73,23 @@ impl Prompt {
         }
     }
 
+    pub fn set_keep_case(&mut self, keep_case: bool) {
+        self.keep_case = keep_case;
+    }
+
+    /// Inserts a character at the cursor, accented letters and punctuation included. Letters are uppercased when it keeps a single character, unless the case is kept
     pub fn input_char(&mut self, c: char) {
-        if !(c.is_ascii_alphanumeric() || c == ' ') || self.input.len() > Self::MAX_INPUT_LEN {
+        if c.is_control() || self.len() > Self::MAX_INPUT_LEN {
             return;
         }
-        let c = c.to_ascii_uppercase();
-
-        if self.cursor_index == self.input.len() {
-            self.input.push(c);
-        } else {
-            let (before, after) = self.input.split_at(self.cursor_index);
-            self.input = format!("{before}{c}{after}");
-        }
+        let mut uppercase = c.to_uppercase();
+        let c = match (self.keep_case, uppercase.next(), uppercase.next()) {
+            (false, Some(upper), None) => upper,
+            _ => c,
+        };
+
+        let index = self.byte_index();
+        self.input.insert(index, c);
         self.cursor_index += 1
     }
 
@@ -77,14 +98,16 @@ impl Prompt {
             return;
         }
         self.cursor_index -= 1;
-        self.input.remove(self.cursor_index);
+        let index = self.byte_index();
+        self.input.remove(index);
     }
 
     pub fn delete_right(&mut self) {
-        if self.cursor_index == self.input.len() {
+        if self.cursor_index == self.len() {
             return;
         }
-        self.input.remove(self.cursor_index);
+        let index = self.byte_index();
+        self.input.remove(index);
     }
 
     pub fn cursor_left(&mut self) {
@@ -92,7 +115,7 @@ impl Prompt {
     }
 
     pub fn cursor_right(&mut self) {
-        self.cursor_index = usize::min(self.cursor_index + 1, self.input.len())
+        self.cursor_index = usize::min(self.cursor_index + 1, self.len())
     }
 
     pub fn cursor_start(&mut self) {
@@ -100,7 +123,7 @@ impl Prompt {
     }
 
     pub fn cursor_end(&mut self) {
-        self.cursor_index = self.input.len();
+        self.cursor_index = self.len();
     }
 
     /// Returns a trimmed input string. `None` if empty
@@ -113,3 +136,54 @@ impl Prompt {
         Some(output)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_byte_input() {
+        let mut prompt = Prompt::default();
+        for c in "été ß?".chars() {
+            prompt.input_char(c);
+        }
+        assert_eq!(prompt.input, "ÉTÉ ß?");
+        prompt.cursor_start();
+        prompt.cursor_right();
+        prompt.delete_left();
+        prompt.input_char('x');
+        prompt.cursor_end();
+        prompt.cursor_left();
+        prompt.delete_left();
+        assert_eq!(prompt.input, "XTÉ ?");
+        assert_eq!(prompt.cursor_index, 4);
+        prompt.delete_right();
+        assert_eq!(prompt.submit().as_deref(), Some("XTÉ"));
+    }
+
+    #[test]
+    fn symbol_input() {
+        let typed = |input: &str| {
+            let mut prompt = Prompt::default();
+            input.chars().for_each(|c| prompt.input_char(c));
+            prompt.input
+        };
+        // Encryption patterns
+        assert_eq!(typed("l t??e"), "L T??E");
+        assert_eq!(typed("l ?[aeiou]??"), "L ?[AEIOU]??");
+        assert_eq!(typed("l\t"), "L");
+        // Unknown letters of decrypted words
+        assert_eq!(typed("pe?k ?e[ao]k"), "PE?K ?E[AO]K");
+        // Punctuation of free-form text, keeping its case
+        let mut prompt = Prompt::default();
+        prompt.set_keep_case(true);
+        let text = "Dear PIGS, the SAND! (it's \"here\"): ok.";
+        text.chars().for_each(|c| prompt.input_char(c));
+        assert_eq!(prompt.submit().as_deref(), Some(text));
+        // Separators of number groups
+        assert_eq!(
+            typed("34 67 22 4, 1 2 3; 4 5 6 | 7 8 9"),
+            "34 67 22 4, 1 2 3; 4 5 6 | 7 8 9"
+        );
+    }
+}